@@ -0,0 +1,107 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use figgie_auto::{MatchMaker, SelfTradePrevention, Checkpoint, Card, Order, Cancel, Direction, Request, Event, PlayerName, QUIET};
+use libfuzzer_sys::fuzz_target;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tokio::sync::broadcast;
+
+
+// =-= Order Processing =-= //
+// - arbitrary orders and cancels (from anyone, at any price) straight into `MatchMaker::process`
+// - the odd `NextRound` settles the round and deals again, so quotes get to outlive the hands and points behind them
+// - after every step nothing may have panicked, and cards, points and resting quotes must all still add up (see `match_maker/audit.rs`)
+
+#[derive(Debug, Arbitrary)]
+enum Seat {
+    Seated(u8), // taken modulo the number of seats
+    Unseated(u8),
+    Nobody,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Order { seat: Seat, price: usize, buy: bool, card: u8 },
+    Cancel { seat: Seat, buy: bool, card: u8 },
+    NextRound,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    seed: u64,
+    players: u8,
+    self_trade_prevention: u8,
+    ops: Vec<Op>,
+}
+
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+fn player_name(engine: &MatchMaker, seat: &Seat) -> PlayerName {
+    match seat {
+        Seat::Seated(idx) => engine.player_names[*idx as usize % engine.player_names.len()].clone(),
+        Seat::Unseated(idx) => PlayerName::External(format!("ghost{}", idx)),
+        Seat::Nobody => PlayerName::None,
+    }
+}
+
+fn direction(buy: bool) -> Direction {
+    if buy { Direction::Buy } else { Direction::Sell }
+}
+
+fn check(engine: &MatchMaker, checkpoint: Checkpoint, op: &Op) {
+    let violations = engine.audit(checkpoint);
+    assert!(violations.is_empty(), "after {:?}: {:?}", op, violations);
+}
+
+fn new_round(engine: &mut MatchMaker) {
+    engine.collect_ante(200 / engine.player_names.len());
+    engine.deal();
+    engine.pull_uncovered_quotes();
+}
+
+fuzz_target!(|input: Input| {
+    QUIET.store(true, Ordering::Relaxed);
+
+    let players = 1 + input.players as usize % 8;
+    let player_names: Vec<PlayerName> = (0..players).map(|idx| PlayerName::External(format!("p{}", idx))).collect();
+    let (event_sender, _events) = broadcast::channel::<Event>(100);
+    let (_, order_receiver) = kanal::unbounded_async::<Request>();
+
+    let mut engine = MatchMaker::new(500, player_names, event_sender, Arc::new(order_receiver));
+    engine.seed(input.seed);
+    engine.self_trade_prevention = match input.self_trade_prevention % 4 {
+        0 => SelfTradePrevention::Allow,
+        1 => SelfTradePrevention::Reject,
+        2 => SelfTradePrevention::CancelIncoming,
+        _ => SelfTradePrevention::CancelResting,
+    };
+    new_round(&mut engine);
+
+    for op in input.ops.iter() {
+        let request = match op {
+            Op::Order { seat, price, buy, card } => Request::Order(Order {
+                player_name: player_name(&engine, seat),
+                price: *price,
+                direction: direction(*buy),
+                card: SUITS[*card as usize % 4].clone(),
+            }),
+            Op::Cancel { seat, buy, card } => Request::Cancel(Cancel {
+                player_name: player_name(&engine, seat),
+                direction: direction(*buy),
+                card: SUITS[*card as usize % 4].clone(),
+            }),
+            Op::NextRound => {
+                engine.settle_round();
+                check(&engine, Checkpoint::Settlement, op);
+                new_round(&mut engine);
+                check(&engine, Checkpoint::Trade, op);
+                continue;
+            },
+        };
+
+        let sequenced = engine.stamp(request);
+        engine.process(sequenced);
+        check(&engine, Checkpoint::Trade, op);
+    }
+});
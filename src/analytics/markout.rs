@@ -0,0 +1,155 @@
+use super::{Card, Trade, Direction, PlayerName, CL, GOAL_CARD_VALUE};
+use serde::{Serialize, Deserialize};
+
+
+// =-= Markouts =-= //
+// - every fill is marked against where the suit traded afterwards: 1 and 3 trades later, the last trade of the round (close),
+// and what the card was worth at settlement
+// - fills are grouped by who was resting (maker) and who crossed the spread to hit them (taker), and marked from the
+// maker's side: negative means the taker knew something the maker didn't, i.e. the maker got picked off
+// - a horizon with no trade to mark against (e.g. the last fill of the round) is left out of that horizon's average
+// - self-trades tell us nothing about anyone, so they're skipped
+
+pub const TRADE_HORIZONS: [usize; 2] = [1, 3];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkoutSum {
+    pub fills: usize,
+    pub total: i64,
+}
+
+impl MarkoutSum {
+    pub fn add(&mut self, markout: i64) {
+        self.fills += 1;
+        self.total += markout;
+    }
+
+    pub fn average(&self) -> Option<f64> {
+        if self.fills == 0 { return None }
+        Some(self.total as f64 / self.fills as f64)
+    }
+
+    pub fn absorb(&mut self, other: &MarkoutSum) {
+        self.fills += other.fills;
+        self.total += other.total;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PairMarkout {
+    pub maker: PlayerName,
+    pub taker: PlayerName,
+    pub fills: usize,
+    pub after: Vec<MarkoutSum>, // one for each of `TRADE_HORIZONS`
+    pub close: MarkoutSum,
+    pub settlement: MarkoutSum,
+}
+
+impl PairMarkout {
+    pub fn new(maker: PlayerName, taker: PlayerName) -> Self {
+        Self {
+            maker,
+            taker,
+            fills: 0,
+            after: vec![MarkoutSum::default(); TRADE_HORIZONS.len()],
+            close: MarkoutSum::default(),
+            settlement: MarkoutSum::default(),
+        }
+    }
+
+    pub fn absorb(&mut self, other: &PairMarkout) {
+        self.fills += other.fills;
+        for (total, markout) in self.after.iter_mut().zip(other.after.iter()) {
+            total.absorb(markout);
+        }
+        self.close.absorb(&other.close);
+        self.settlement.absorb(&other.settlement);
+    }
+}
+
+pub fn absorb_markouts(totals: &mut Vec<PairMarkout>, markouts: &[PairMarkout]) {
+    for markout in markouts.iter() {
+        match totals.iter_mut().find(|total| total.maker == markout.maker && total.taker == markout.taker) {
+            Some(total) => total.absorb(markout),
+            None => totals.push(markout.clone()),
+        }
+    }
+}
+
+fn maker_markout(trade: &Trade, reference: usize) -> i64 {
+    // the maker sold if the taker bought, and the other way around
+    match trade.aggressor {
+        Direction::Buy => trade.price as i64 - reference as i64,
+        Direction::Sell => reference as i64 - trade.price as i64,
+    }
+}
+
+pub fn markouts(trades: &[Trade], goal_suit: &Card) -> Vec<PairMarkout> {
+    let mut pairs: Vec<PairMarkout> = Vec::new();
+
+    for (idx, trade) in trades.iter().enumerate() {
+        if trade.buyer == trade.seller {
+            continue;
+        }
+        let (maker, taker) = match trade.aggressor {
+            Direction::Buy => (&trade.seller, &trade.buyer),
+            Direction::Sell => (&trade.buyer, &trade.seller),
+        };
+        let later: Vec<usize> = trades[idx + 1..].iter().filter(|later| later.card == trade.card).map(|later| later.price).collect();
+        let card_value = if trade.card == *goal_suit { GOAL_CARD_VALUE } else { 0 };
+
+        let position = match pairs.iter().position(|pair| pair.maker == *maker && pair.taker == *taker) {
+            Some(position) => position,
+            None => {
+                pairs.push(PairMarkout::new(maker.clone(), taker.clone()));
+                pairs.len() - 1
+            },
+        };
+        let pair = &mut pairs[position];
+        pair.fills += 1;
+        for (horizon, sum) in TRADE_HORIZONS.iter().zip(pair.after.iter_mut()) {
+            if let Some(reference) = later.get(horizon - 1) {
+                sum.add(maker_markout(trade, *reference));
+            }
+        }
+        if let Some(close) = later.last() {
+            pair.close.add(maker_markout(trade, *close));
+        }
+        pair.settlement.add(maker_markout(trade, card_value));
+    }
+
+    pairs
+}
+
+fn average(sum: &MarkoutSum) -> String {
+    match sum.average() {
+        Some(average) => format!("{:+.1}", average),
+        None => String::from("-"),
+    }
+}
+
+pub fn print_markouts(title: &str, markouts: &[PairMarkout]) {
+    if markouts.is_empty() {
+        return;
+    }
+    console!("=---= {} (maker <- taker, from the maker's side) =---=", title);
+    for pair in markouts.iter() {
+        let after: Vec<String> = TRADE_HORIZONS.iter().zip(pair.after.iter()).map(|(horizon, sum)| format!("+{}: {}", horizon, average(sum))).collect();
+        let settlement_color = match pair.settlement.total {
+            x if x > 0 => CL::Green.get(),
+            x if x < 0 => CL::Red.get(),
+            _ => CL::Dull.get(),
+        };
+        narrate!(
+            info!(
+                report = title, maker = %pair.maker, taker = %pair.taker, fills = pair.fills,
+                after = ?pair.after.iter().map(MarkoutSum::average).collect::<Vec<_>>(), close = ?pair.close.average(), settle = ?pair.settlement.average(),
+                "markout",
+            ),
+            "{}{}{:?}{} <- {}{:?}{} |:| Fills: {} | {} | Close: {} | Settle: {}{}{}",
+            CL::Dull.get(), CL::DimLightBlue.get(), pair.maker, CL::Dull.get(), CL::DimLightBlue.get(), pair.taker, CL::Dull.get(),
+            pair.fills, after.join(" | "), average(&pair.close), settlement_color, average(&pair.settlement), CL::End.get(),
+        );
+    }
+    console!();
+}
@@ -0,0 +1,307 @@
+use super::{Card, Trade, Direction, PlayerName, CL};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use tracing::error;
+use std::path::PathBuf;
+
+pub mod markout;
+pub use markout::*;
+
+pub mod tables;
+pub use tables::*;
+
+
+// =-= Analytics =-= //
+// - every round, each player's point change is broken down into where it came from:
+// - ante: what they paid to sit down (negative)
+// - trading: cash in from cards sold minus cash out for cards bought
+// - goal payout: 10 for every goal card held at the end
+// - bonus: their share of whatever was left of the pot
+// - on top of that, per suit: how much they bought and sold, at what average price, and how that compares to what
+// the card turned out to be worth (10 for the goal suit, nothing for the rest)
+// - spread capture is the round-trip profit on cards bought and sold back in the same suit, what a market maker lives off
+// - fills are also marked out per maker/taker pair, see `markout.rs`
+// - rounds are added up into a session, `--report <dir>` writes both out as JSON after every round
+// - the raw trades, quotes, deals and results can be written out as CSV tables as well, see `tables.rs`
+
+pub const GOAL_CARD_VALUE: usize = 10;
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payout {
+    pub goal: usize,
+    pub bonus: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuitStats {
+    pub bought: usize,
+    pub sold: usize,
+    pub buy_cost: usize,
+    pub sell_proceeds: usize,
+    pub bought_value: usize, // what the cards bought turned out to be worth at settlement
+    pub sold_value: usize,
+}
+
+impl SuitStats {
+    pub fn volume(&self) -> usize {
+        self.bought + self.sold
+    }
+
+    pub fn avg_buy_price(&self) -> Option<f64> {
+        if self.bought == 0 { return None }
+        Some(self.buy_cost as f64 / self.bought as f64)
+    }
+
+    pub fn avg_sell_price(&self) -> Option<f64> {
+        if self.sold == 0 { return None }
+        Some(self.sell_proceeds as f64 / self.sold as f64)
+    }
+
+    pub fn spread_capture(&self) -> f64 {
+        // only the cards that made the round trip count, the rest is a position, not a spread
+        match (self.avg_buy_price(), self.avg_sell_price()) {
+            (Some(buy), Some(sell)) => self.bought.min(self.sold) as f64 * (sell - buy),
+            _ => 0.0,
+        }
+    }
+
+    pub fn edge(&self) -> i64 {
+        // what the trades were worth against the cards' final value: buying under it and selling over it are both good
+        (self.bought_value as i64 - self.buy_cost as i64) + (self.sell_proceeds as i64 - self.sold_value as i64)
+    }
+
+    pub fn absorb(&mut self, other: &SuitStats) {
+        self.bought += other.bought;
+        self.sold += other.sold;
+        self.buy_cost += other.buy_cost;
+        self.sell_proceeds += other.sell_proceeds;
+        self.bought_value += other.bought_value;
+        self.sold_value += other.sold_value;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerReport {
+    pub player_name: PlayerName,
+    pub ante: i64,
+    pub trading: i64,
+    pub goal_payout: i64,
+    pub bonus: i64,
+    pub pnl: i64,
+    pub trades: usize,
+    pub suits: HashMap<Card, SuitStats>,
+}
+
+impl PlayerReport {
+    pub fn new(player_name: PlayerName) -> Self {
+        Self {
+            player_name,
+            ante: 0,
+            trading: 0,
+            goal_payout: 0,
+            bonus: 0,
+            pnl: 0,
+            trades: 0,
+            suits: SUITS.iter().map(|card| (card.clone(), SuitStats::default())).collect(),
+        }
+    }
+
+    pub fn suit(&mut self, card: &Card) -> &mut SuitStats {
+        self.suits.entry(card.clone()).or_default()
+    }
+
+    pub fn volume(&self) -> usize {
+        self.suits.values().map(|stats| stats.volume()).sum()
+    }
+
+    pub fn avg_buy_price(&self) -> Option<f64> {
+        let bought: usize = self.suits.values().map(|stats| stats.bought).sum();
+        if bought == 0 { return None }
+        Some(self.suits.values().map(|stats| stats.buy_cost).sum::<usize>() as f64 / bought as f64)
+    }
+
+    pub fn avg_sell_price(&self) -> Option<f64> {
+        let sold: usize = self.suits.values().map(|stats| stats.sold).sum();
+        if sold == 0 { return None }
+        Some(self.suits.values().map(|stats| stats.sell_proceeds).sum::<usize>() as f64 / sold as f64)
+    }
+
+    pub fn spread_capture(&self) -> f64 {
+        self.suits.values().map(|stats| stats.spread_capture()).sum()
+    }
+
+    pub fn edge(&self) -> i64 {
+        self.suits.values().map(|stats| stats.edge()).sum()
+    }
+
+    pub fn absorb(&mut self, other: &PlayerReport) {
+        self.ante += other.ante;
+        self.trading += other.trading;
+        self.goal_payout += other.goal_payout;
+        self.bonus += other.bonus;
+        self.pnl += other.pnl;
+        self.trades += other.trades;
+        for (card, stats) in other.suits.iter() {
+            self.suit(card).absorb(stats);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundReport {
+    pub round: u32,
+    pub common_suit: Card,
+    pub goal_suit: Card,
+    pub trades: usize,
+    pub players: Vec<PlayerReport>, // in seat order
+    pub markouts: Vec<PairMarkout>,
+}
+
+impl RoundReport {
+    pub fn new(
+        round: u32,
+        common_suit: Card,
+        goal_suit: Card,
+        player_names: &[PlayerName],
+        antes: &HashMap<PlayerName, usize>,
+        trades: &[Trade],
+        payouts: &HashMap<PlayerName, Payout>,
+    ) -> Self {
+
+        let mut players: Vec<PlayerReport> = player_names.iter().map(|player_name| PlayerReport::new(player_name.clone())).collect();
+        for report in players.iter_mut() {
+            report.ante = -(antes.get(&report.player_name).copied().unwrap_or(0) as i64);
+            let payout = payouts.get(&report.player_name).copied().unwrap_or_default();
+            report.goal_payout = payout.goal as i64;
+            report.bonus = payout.bonus as i64;
+        }
+
+        for trade in trades.iter() {
+            let card_value = if trade.card == goal_suit { GOAL_CARD_VALUE } else { 0 };
+            if let Some(buyer) = players.iter_mut().find(|report| report.player_name == trade.buyer) {
+                buyer.trading -= trade.price as i64;
+                buyer.trades += 1;
+                let stats = buyer.suit(&trade.card);
+                stats.bought += 1;
+                stats.buy_cost += trade.price;
+                stats.bought_value += card_value;
+            }
+            if let Some(seller) = players.iter_mut().find(|report| report.player_name == trade.seller) {
+                seller.trading += trade.price as i64;
+                if trade.seller != trade.buyer {
+                    seller.trades += 1;
+                }
+                let stats = seller.suit(&trade.card);
+                stats.sold += 1;
+                stats.sell_proceeds += trade.price;
+                stats.sold_value += card_value;
+            }
+        }
+
+        for report in players.iter_mut() {
+            report.pnl = report.ante + report.trading + report.goal_payout + report.bonus;
+        }
+
+        let markouts = markouts(trades, &goal_suit);
+        Self { round, common_suit, goal_suit, trades: trades.len(), players, markouts }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub rounds: usize,
+    pub trades: usize,
+    pub players: Vec<PlayerReport>, // in the order they first showed up
+    pub markouts: Vec<PairMarkout>,
+}
+
+impl SessionReport {
+    pub fn absorb(&mut self, round: &RoundReport) {
+        self.rounds += 1;
+        self.trades += round.trades;
+        for report in round.players.iter() {
+            match self.players.iter_mut().find(|total| total.player_name == report.player_name) {
+                Some(total) => total.absorb(report),
+                None => self.players.push(report.clone()),
+            }
+        }
+        absorb_markouts(&mut self.markouts, &round.markouts);
+    }
+}
+
+
+// =-= Analytics =-= //
+// - owned by the match maker, which hands it a `RoundReport` once each round is settled
+// - only keeps the rounds themselves when asked to, a live table plays forever and only needs the session totals
+
+#[derive(Debug, Clone, Default)]
+pub struct Analytics {
+    pub session: SessionReport,
+    pub rounds: Vec<RoundReport>, // every round so far when `keep_rounds` is set, for whoever wants more than the totals (e.g. ratings)
+    pub keep_rounds: bool,
+    pub output: Option<PathBuf>, // directory for `rounds.jsonl` (one line a round) and `session.json` (rewritten every round)
+}
+
+impl Analytics {
+    pub fn new(output: Option<PathBuf>) -> Self {
+        Self { session: SessionReport::default(), rounds: Vec::new(), keep_rounds: false, output }
+    }
+
+    pub fn record(&mut self, round: RoundReport) {
+        self.session.absorb(&round);
+        print_report(&format!("Round {} PnL", round.round), &round.players);
+        print_report(&format!("Session PnL ({} rounds)", self.session.rounds), &self.session.players);
+        print_markouts(&format!("Session Markouts ({} rounds)", self.session.rounds), &self.session.markouts);
+
+        if let Err(e) = self.export(&round) {
+            error!(error = %e, "unable to write the analytics report");
+        }
+        if self.keep_rounds {
+            self.rounds.push(round);
+        }
+    }
+
+    pub fn export(&self, round: &RoundReport) -> io::Result<()> {
+        let Some(output) = &self.output else { return Ok(()) };
+        fs::create_dir_all(output)?;
+
+        let mut rounds = OpenOptions::new().create(true).append(true).open(output.join("rounds.jsonl"))?;
+        writeln!(rounds, "{}", serde_json::to_string(round)?)?;
+        fs::write(output.join("session.json"), serde_json::to_string_pretty(&self.session)?)?;
+        Ok(())
+    }
+}
+
+fn price(price: Option<f64>) -> String {
+    match price {
+        Some(price) => format!("{:.1}", price),
+        None => String::from("-"),
+    }
+}
+
+pub fn print_report(title: &str, players: &[PlayerReport]) {
+    console!("=---= {} =---=", title);
+    for report in players.iter() {
+        let pnl_color = match report.pnl {
+            x if x > 0 => CL::Green.get(),
+            x if x < 0 => CL::Red.get(),
+            _ => CL::Dull.get(),
+        };
+        narrate!(
+            info!(
+                report = title, player = %report.player_name, ante = report.ante, trading = report.trading, goal = report.goal_payout, bonus = report.bonus, pnl = report.pnl,
+                trades = report.trades, volume = report.volume(), avg_buy = ?report.avg_buy_price(), avg_sell = ?report.avg_sell_price(), spread = report.spread_capture(), edge = report.edge(),
+                "pnl",
+            ),
+            "{}{}{:?}{} |:| Ante: {} | Trading: {:+} | Goal: {} | Bonus: {} | PnL: {}{:+}{} |:| Trades: {} | Volume: {} | Avg buy: {} | Avg sell: {} | Spread: {:+.1} | Edge: {:+}{}",
+            CL::Dull.get(), CL::DimLightBlue.get(), report.player_name, CL::Dull.get(),
+            report.ante, report.trading, report.goal_payout, report.bonus, pnl_color, report.pnl, CL::Dull.get(),
+            report.trades, report.volume(), price(report.avg_buy_price()), price(report.avg_sell_price()), report.spread_capture(), report.edge(),
+            CL::End.get(),
+        );
+    }
+    console!();
+}
@@ -0,0 +1,163 @@
+use super::{Card, Trade, PlayerName, RoundReport};
+use super::super::{Book, Inventory};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+
+// =-= Tables =-= //
+// - flat CSV tables of a session for notebooks (`pd.read_csv`, `pl.read_csv`, ...), `--export <dir>` writes them:
+// - `trades.csv`: round, seq, timestamp, suit, price, buyer, seller, aggressor
+// - `quotes.csv`: top of book for a suit every time it changes (bid, bid owner, ask, ask owner, last trade), an empty side is left blank
+// - `deals.csv`: every player's hand as dealt, with the round's common and goal suits
+// - `results.csv`: every player's round PnL broken down (see `RoundReport`) and their points after settlement
+// - timestamps are microseconds since the engine started, the same clock as `Stamp` and `Trade::timestamp`
+// - rows are held for the round and appended once it's settled, the files are started over at the first round of a session
+// - with no directory nothing is kept
+
+pub const TRADES_HEADER: &str = "round,seq,timestamp,suit,price,buyer,seller,aggressor";
+pub const QUOTES_HEADER: &str = "round,timestamp,suit,bid,bidder,ask,asker,last_trade";
+pub const DEALS_HEADER: &str = "round,player,spades,clubs,diamonds,hearts,common_suit,goal_suit";
+pub const RESULTS_HEADER: &str = "round,player,ante,trading,goal_payout,bonus,pnl,trades,points";
+
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+pub fn csv_field(field: &str) -> String {
+    // external bots name themselves, so a name could carry a comma or a quote
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+    // the other way round from `csv_field`, quoted fields can hold commas and doubled quotes
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+pub fn read_table(path: &Path) -> io::Result<Vec<HashMap<String, String>>> {
+    // every row keyed by the header, for reading a session back in (see `report/mod.rs`)
+    let text = fs::read_to_string(path)?;
+    let mut lines = text.lines();
+    let header = lines.next().map(parse_csv_line).unwrap_or_default();
+    Ok(lines
+        .filter(|line| !line.is_empty())
+        .map(|line| header.iter().cloned().zip(parse_csv_line(line)).collect())
+        .collect())
+}
+
+fn name(player_name: &PlayerName) -> String {
+    match player_name {
+        PlayerName::None => String::new(),
+        player_name => csv_field(&String::from(player_name.clone())),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Tables {
+    pub output: Option<PathBuf>,
+    pub started: bool, // headers written
+    pub quotes: Vec<String>,
+    pub deals: Vec<String>,
+    pub last_quotes: HashMap<Card, String>, // to only write a suit's top of book when it moves, a requote at the same price isn't a move
+}
+
+impl Tables {
+    pub fn new(output: Option<PathBuf>) -> Self {
+        Self { output, ..Self::default() }
+    }
+
+    pub fn record_quotes(&mut self, round: u32, timestamp: u64, books: &HashMap<Card, Book>) {
+        if self.output.is_none() {
+            return;
+        }
+        for suit in SUITS.iter() {
+            let Some(book) = books.get(suit) else { continue };
+            let bid = if book.bid.player_name == PlayerName::None { String::new() } else { book.bid.price.to_string() };
+            let ask = if book.ask.player_name == PlayerName::None { String::new() } else { book.ask.price.to_string() };
+            let last_trade = book.last_trade.map(|price| price.to_string()).unwrap_or_default();
+            let quote = format!("{},{},{},{},{}", bid, name(&book.bid.player_name), ask, name(&book.ask.player_name), last_trade);
+            if self.last_quotes.get(suit) == Some(&quote) {
+                continue;
+            }
+            self.quotes.push(format!("{},{},{:?},{}", round, timestamp, suit, quote));
+            self.last_quotes.insert(suit.clone(), quote);
+        }
+    }
+
+    pub fn record_deal(&mut self, round: u32, player_names: &[PlayerName], inventories: &HashMap<PlayerName, Inventory>, common_suit: &Card, goal_suit: &Card) {
+        if self.output.is_none() {
+            return;
+        }
+        for player_name in player_names.iter() {
+            let hand = inventories.get(player_name).copied().unwrap_or_default();
+            self.deals.push(format!("{},{},{},{},{},{},{:?},{:?}", round, name(player_name), hand.spades, hand.clubs, hand.diamonds, hand.hearts, common_suit, goal_suit));
+        }
+    }
+
+    pub fn trade_rows(round: u32, trades: &[Trade]) -> Vec<String> {
+        trades.iter()
+            .map(|trade| format!("{},{},{},{:?},{},{},{},{:?}", round, trade.seq, trade.timestamp, trade.card, trade.price, name(&trade.buyer), name(&trade.seller), trade.aggressor))
+            .collect()
+    }
+
+    pub fn result_rows(report: &RoundReport, points: &HashMap<PlayerName, usize>) -> Vec<String> {
+        report.players.iter()
+            .map(|player| format!(
+                "{},{},{},{},{},{},{},{},{}",
+                report.round, name(&player.player_name), player.ante, player.trading, player.goal_payout, player.bonus, player.pnl, player.trades,
+                points.get(&player.player_name).copied().unwrap_or(0),
+            ))
+            .collect()
+    }
+
+    pub fn record_round(&mut self, report: &RoundReport, trades: &[Trade], points: &HashMap<PlayerName, usize>) -> io::Result<()> {
+        let Some(output) = self.output.clone() else { return Ok(()) };
+        let quotes = std::mem::take(&mut self.quotes);
+        let deals = std::mem::take(&mut self.deals);
+        fs::create_dir_all(&output)?;
+
+        let started = self.started;
+        let open = |file: &str, header: &str| -> io::Result<File> {
+            if started {
+                OpenOptions::new().create(true).append(true).open(output.join(file))
+            } else {
+                let mut file = File::create(output.join(file))?;
+                writeln!(file, "{}", header)?;
+                Ok(file)
+            }
+        };
+
+        for (file, header, rows) in [
+            ("trades.csv", TRADES_HEADER, Self::trade_rows(report.round, trades)),
+            ("quotes.csv", QUOTES_HEADER, quotes),
+            ("deals.csv", DEALS_HEADER, deals),
+            ("results.csv", RESULTS_HEADER, Self::result_rows(report, points)),
+        ] {
+            let mut file = open(file, header)?;
+            for row in rows.iter() {
+                writeln!(file, "{}", row)?;
+            }
+        }
+        self.started = true;
+        Ok(())
+    }
+}
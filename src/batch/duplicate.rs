@@ -0,0 +1,164 @@
+use super::{BatchConfig, GameResult, RateBy, play_game, PlayerName, QUIET, CL};
+use super::super::rating::Ratings;
+use std::sync::atomic::Ordering;
+use tracing::{warn, error};
+
+
+// =-= Duplicate =-= //
+// - like duplicate bridge: every deal is played once for each seat, with the lineup rotated one seat along each time,
+// so every player gets dealt every hand and sits next to the same neighbours
+// - a seat's value is what the players who sat there made on average, i.e. how good those cards were
+// - a player's seat-adjusted score is what they made in a seat minus that seat's value, averaged over the seats,
+// so a strong hand played badly counts against you and a weak hand played well counts for you
+// - ratings are fed one result per deal, on the seat-adjusted scores
+// - deals need a seed to be replayed, without one a random base seed is picked and printed so the tournament can be rerun
+
+#[derive(Debug, Clone)]
+pub struct DuplicateScore {
+    pub player_name: PlayerName,
+    pub seats: Vec<i64>, // what they made in each seat, by seat
+    pub adjusted: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DealResult {
+    pub deal: usize,
+    pub seed: u64,
+    pub seat_values: Vec<f64>,
+    pub scores: Vec<DuplicateScore>, // in lineup order
+    pub games: Vec<GameResult>, // one per rotation
+}
+
+impl DealResult {
+    pub fn new(deal: usize, seed: u64, lineup: &[PlayerName], games: Vec<GameResult>, starting_balance: usize) -> Self {
+        let seats = lineup.len();
+        let mut seat_values = vec![0.0; seats];
+        let mut scores: Vec<DuplicateScore> = lineup.iter()
+            .map(|player_name| DuplicateScore { player_name: player_name.clone(), seats: vec![0; seats], adjusted: 0.0 })
+            .collect();
+
+        for game in games.iter() {
+            for (seat, (player_name, points)) in game.points.iter().enumerate() {
+                let pnl = *points as i64 - starting_balance as i64;
+                seat_values[seat] += pnl as f64 / games.len() as f64;
+                if let Some(score) = scores.iter_mut().find(|score| score.player_name == *player_name) {
+                    score.seats[seat] = pnl;
+                }
+            }
+        }
+        for score in scores.iter_mut() {
+            let adjusted: f64 = score.seats.iter().zip(seat_values.iter()).map(|(pnl, value)| *pnl as f64 - value).sum();
+            score.adjusted = adjusted / seats as f64;
+        }
+
+        Self { deal, seed, seat_values, scores, games }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateTotal {
+    pub player_name: PlayerName,
+    pub deals: usize,
+    pub adjusted: f64, // summed over deals
+    pub adjusted_squared: f64,
+    pub points: i64, // raw, summed over every game
+}
+
+impl DuplicateTotal {
+    pub fn mean(&self) -> f64 {
+        if self.deals == 0 { return 0.0 }
+        self.adjusted / self.deals as f64
+    }
+
+    pub fn std_error(&self) -> f64 {
+        // how far the mean could be from the truth given how much it swings deal to deal
+        if self.deals < 2 { return 0.0 }
+        let n = self.deals as f64;
+        let variance = ((self.adjusted_squared - self.adjusted * self.adjusted / n) / (n - 1.0)).max(0.0);
+        (variance / n).sqrt()
+    }
+}
+
+pub fn duplicate_totals(deals: &[DealResult]) -> Vec<DuplicateTotal> {
+    let mut totals: Vec<DuplicateTotal> = Vec::new();
+    for deal in deals.iter() {
+        for score in deal.scores.iter() {
+            let position = match totals.iter().position(|total| total.player_name == score.player_name) {
+                Some(position) => position,
+                None => {
+                    totals.push(DuplicateTotal { player_name: score.player_name.clone(), deals: 0, adjusted: 0.0, adjusted_squared: 0.0, points: 0 });
+                    totals.len() - 1
+                },
+            };
+            let total = &mut totals[position];
+            total.deals += 1;
+            total.adjusted += score.adjusted;
+            total.adjusted_squared += score.adjusted * score.adjusted;
+            total.points += score.seats.iter().sum::<i64>();
+        }
+    }
+    totals.sort_by(|a, b| b.mean().total_cmp(&a.mean()));
+    totals
+}
+
+
+pub fn run_duplicate(config: &BatchConfig, ratings: &mut Ratings) -> Vec<DealResult> {
+    let base_seed = config.seed.unwrap_or_else(rand::random::<u64>);
+    if config.seed.is_none() {
+        console!("{}[+] Duplicate deals from seed {} (pass --seed {} to replay them){}", CL::DimLightBlue.get(), base_seed, base_seed, CL::End.get());
+    }
+    if config.rate_by == RateBy::Round {
+        warn!("duplicate deals are rated per deal, ignoring --rate-by round");
+    }
+    let seats = config.lineup.len();
+    let quiet = QUIET.load(Ordering::Relaxed);
+    let mut deals = Vec::new();
+
+    for deal in 0..config.games {
+        let seed = base_seed.wrapping_add(deal as u64);
+        let mut games = Vec::new();
+        for rotation in 0..seats {
+            let mut lineup = config.lineup.clone();
+            lineup.rotate_right(rotation); // everyone moves one seat along
+            QUIET.store(true, Ordering::Relaxed);
+            games.push(play_game(config, deal * seats + rotation, &lineup, Some(seed)));
+            QUIET.store(quiet, Ordering::Relaxed);
+        }
+        let result = DealResult::new(deal, seed, &config.lineup, games, config.starting_balance);
+
+        let standings: Vec<(String, i64)> = result.scores.iter().map(|score| (config.label(&score.player_name), score.adjusted.round() as i64)).collect();
+        ratings.update(&standings);
+        if let Some(path) = &config.ratings_path {
+            if let Err(e) = ratings.save(path) {
+                error!(path = ?path, error = %e, "unable to save ratings");
+            }
+        }
+
+        let seat_values: Vec<String> = result.seat_values.iter().map(|value| format!("{:+}", value.round() as i64)).collect();
+        let mut scores: Vec<&DuplicateScore> = result.scores.iter().collect();
+        scores.sort_by(|a, b| b.adjusted.total_cmp(&a.adjusted));
+        let adjusted: Vec<(String, &[i64], f64)> = scores.iter().map(|score| (config.label(&score.player_name), score.seats.as_slice(), score.adjusted)).collect();
+        let scores: Vec<String> = scores.iter().map(|score| format!("{}: {:+.1}", config.label(&score.player_name), score.adjusted)).collect();
+        narrate!(
+            info!(deal = deal + 1, deals = config.games, seed, seat_values = ?result.seat_values, scores = ?adjusted, "deal over"),
+            "{}[+] Deal {}/{} (seed {}) |:| Seats: {} |:| {}{}", CL::DullGreen.get(), deal + 1, config.games, seed, seat_values.join(" "), scores.join(" | "), CL::End.get()
+        );
+
+        deals.push(result);
+    }
+
+    console!();
+    console!("=---= Duplicate Results ({} deals x {} seats) =---=", deals.len(), seats);
+    for total in duplicate_totals(&deals).iter() {
+        let color = if total.mean() >= 0.0 { CL::Green.get() } else { CL::Red.get() };
+        narrate!(
+            info!(player = %config.label(&total.player_name), deals = total.deals, adjusted = total.mean(), std_error = total.std_error(), points = total.points, "duplicate result"),
+            "{}{}{}{} |:| Seat-adjusted: {}{:+.1}{} ± {:.1} | Raw points: {:+}{}",
+            CL::Dull.get(), CL::DimLightBlue.get(), config.label(&total.player_name), CL::Dull.get(),
+            color, total.mean(), CL::Dull.get(), total.std_error(), total.points, CL::End.get(),
+        );
+    }
+    console!();
+    ratings.print_leaderboard();
+    deals
+}
@@ -0,0 +1,183 @@
+use super::{MatchMaker, PlayerName, Request, Event, EVENT_CAPACITY, QUIET, CL};
+use super::player::spawn_player;
+use super::analytics::{RoundReport, SessionReport, Tables};
+use super::rating::Ratings;
+use super::player::StrategyParams;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tracing::error;
+
+pub mod duplicate;
+pub use duplicate::*;
+
+
+// =-= Batch =-= //
+// - plays game after game with nobody watching, on a paused clock: tokio skips ahead whenever every task is waiting,
+// so a 4 minute round takes as long as the bots take to think instead of 4 minutes
+// - the paused clock is tokio's `test-util`, so all of this (and `tuning`) is behind the `batch` feature
+// - each game gets a runtime of its own, so nothing from one game (tasks, timers, channels) leaks into the next
+// - with a seed, game g deals from seed + g, so rerunning a batch deals the same cards (the bots' own dice are still their own)
+// - only our own bots can play, there's nobody on the other end of a gateway seat to keep up with a paused clock
+// - `duplicate.rs` replays each deal with everyone rotated through every seat, so the cards stop deciding who wins
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateBy {
+    Round,
+    Game,
+}
+
+impl FromStr for RateBy {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "round" => Ok(RateBy::Round),
+            "game" => Ok(RateBy::Game),
+            _ => Err(format!("unknown '{}', pick from round or game", text)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub games: usize,
+    pub rounds: u32, // per game
+    pub lineup: Vec<PlayerName>,
+    pub seed: Option<u64>,
+    pub starting_balance: usize,
+    pub rate_by: RateBy,
+    pub ratings_path: Option<PathBuf>,
+    pub params: HashMap<PlayerName, StrategyParams>, // anyone not in here plays their defaults
+    pub export: Option<PathBuf>, // each game's tables go in a `game-<n>` directory under this
+}
+
+impl BatchConfig {
+    pub fn new(games: usize, lineup: Vec<PlayerName>) -> Self {
+        Self {
+            games,
+            rounds: 5,
+            lineup,
+            seed: None,
+            starting_balance: 500,
+            rate_by: RateBy::Game,
+            ratings_path: None,
+            params: HashMap::new(),
+            export: None,
+        }
+    }
+
+    pub fn label(&self, player_name: &PlayerName) -> String {
+        // a seat playing a tuned set is rated under its own label, see `StrategyParams::label`
+        match self.params.get(player_name) {
+            Some(params) => params.label(),
+            None => String::from(player_name.clone()),
+        }
+    }
+
+    pub fn game_seed(&self, game: usize) -> Option<u64> {
+        self.seed.map(|seed| seed.wrapping_add(game as u64))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    pub game: usize,
+    pub seed: Option<u64>,
+    pub points: Vec<(PlayerName, usize)>, // final points, in seat order
+    pub rounds: Vec<RoundReport>,
+    pub session: SessionReport,
+}
+
+impl GameResult {
+    pub fn standings(&self, config: &BatchConfig) -> Vec<(String, i64)> {
+        self.points.iter().map(|(player_name, points)| (config.label(player_name), *points as i64 - config.starting_balance as i64)).collect()
+    }
+
+    pub fn round_standings(&self, config: &BatchConfig) -> Vec<Vec<(String, i64)>> {
+        self.rounds.iter()
+            .map(|round| round.players.iter().map(|report| (config.label(&report.player_name), report.pnl)).collect())
+            .collect()
+    }
+}
+
+
+pub fn play_game(config: &BatchConfig, game: usize, lineup: &[PlayerName], seed: Option<u64>) -> GameResult {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .start_paused(true)
+        .build()
+        .expect("Failed to build runtime");
+
+    runtime.block_on(async {
+        let (tx, rx) = kanal::unbounded_async::<Request>();
+        let order_sender = Arc::new(tx);
+        let (event_sender, _) = tokio::sync::broadcast::channel::<Event>(EVENT_CAPACITY);
+
+        for player_name in lineup.iter() {
+            spawn_player(player_name.clone(), config.params.get(player_name).cloned(), event_sender.clone(), Arc::clone(&order_sender));
+        }
+
+        let mut match_maker: MatchMaker = MatchMaker::new(config.starting_balance, lineup.to_vec(), event_sender, Arc::new(rx));
+        match_maker.rounds = Some(config.rounds);
+        match_maker.analytics.keep_rounds = true; // the ratings and duplicate scores are built from them
+        match_maker.tables = Tables::new(config.export.as_ref().map(|dir| dir.join(format!("game-{}", game))));
+        if let Some(seed) = seed {
+            match_maker.seed(seed);
+        }
+        match_maker.start().await;
+
+        GameResult {
+            game,
+            seed,
+            points: match_maker.player_names.iter()
+                .map(|player_name| (player_name.clone(), match_maker.player_points.get(player_name).copied().unwrap_or(0)))
+                .collect(),
+            rounds: std::mem::take(&mut match_maker.analytics.rounds),
+            session: match_maker.analytics.session.clone(),
+        }
+    })
+    // the players' tasks go down with the runtime
+}
+
+pub fn run_batch(config: &BatchConfig, ratings: &mut Ratings) -> Vec<GameResult> {
+    let mut results = Vec::new();
+    let quiet = QUIET.load(Ordering::Relaxed);
+
+    for game in 0..config.games {
+        QUIET.store(true, Ordering::Relaxed);
+        let result = play_game(config, game, &config.lineup, config.game_seed(game));
+        QUIET.store(quiet, Ordering::Relaxed);
+
+        match config.rate_by {
+            RateBy::Game => ratings.update(&result.standings(config)),
+            RateBy::Round => {
+                for standings in result.round_standings(config) {
+                    ratings.update(&standings);
+                }
+            },
+        }
+        if let Some(path) = &config.ratings_path {
+            if let Err(e) = ratings.save(path) {
+                error!(path = ?path, error = %e, "unable to save ratings");
+            }
+        }
+
+        let mut standings = result.standings(config);
+        standings.sort_by_key(|(_, points)| std::cmp::Reverse(*points));
+        let line: Vec<String> = standings.iter().map(|(label, points)| format!("{}: {:+}", label, points)).collect();
+        let seed = result.seed.map(|seed| format!(" (seed {})", seed)).unwrap_or_default();
+        narrate!(
+            info!(game = game + 1, games = config.games, seed = ?result.seed, trades = result.session.trades, standings = ?standings, "game over"),
+            "{}[+] Game {}/{}{} |:| Trades: {} |:| {}{}", CL::DullGreen.get(), game + 1, config.games, seed, result.session.trades, line.join(" | "), CL::End.get()
+        );
+
+        results.push(result);
+    }
+
+    console!();
+    ratings.print_leaderboard();
+    results
+}
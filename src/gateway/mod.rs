@@ -0,0 +1,231 @@
+use super::{Card, Direction, Inventory, Order, Cancel, Request, Event, Update, Ack, Snapshot, PlayerName};
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::sync::broadcast::Sender;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{watch, Mutex};
+use kanal::AsyncSender;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{info, warn, error};
+
+pub mod protocol;
+pub use protocol::*;
+
+pub mod subprocess;
+pub use subprocess::SubprocessBot;
+
+
+// =-= Gateway =-= //
+// - lets bots written in anything connect over localhost and take one of the `External` seats
+// - a bot logs in with its seat's name, then gets its private deal and the public event stream as JSON lines
+// - everything it sends is stamped with its seat before it reaches the match maker, so it can't trade as someone else
+// - an empty seat still holds its cards, it just doesn't trade until a bot connects
+// - a bot that connects once the cards are out is dealt what its seat holds at that moment, from the match maker's snapshot
+
+pub struct Gateway {
+    pub address: String,
+    pub seats: Vec<PlayerName>,
+    pub connected: Arc<Mutex<HashSet<PlayerName>>>,
+    pub event_sender: Sender<Event>,
+    pub order_sender: Arc<AsyncSender<Request>>,
+    pub snapshot_receiver: watch::Receiver<Snapshot>,
+}
+
+impl Gateway {
+    pub fn new(
+        address: String,
+        seats: Vec<PlayerName>,
+        event_sender: Sender<Event>,
+        order_sender: Arc<AsyncSender<Request>>,
+        snapshot_receiver: watch::Receiver<Snapshot>,
+    ) -> Self {
+        Self {
+            address,
+            seats,
+            connected: Arc::new(Mutex::new(HashSet::new())),
+            event_sender,
+            order_sender,
+            snapshot_receiver,
+        }
+    }
+
+
+
+    pub async fn start(&mut self) {
+        let listener = match TcpListener::bind(&self.address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!(address = %self.address, error = %e, "unable to listen");
+                return;
+            }
+        };
+        info!(address = %self.address, seats = ?self.seats, "listening");
+
+        loop {
+            let (stream, address) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!(error = %e, "unable to accept connection");
+                    continue;
+                }
+            };
+            info!(%address, "connection");
+
+            let seats = self.seats.clone();
+            let connected = self.connected.clone();
+            let event_sender = self.event_sender.clone();
+            let order_sender = self.order_sender.clone();
+            let snapshot_receiver = self.snapshot_receiver.clone();
+            tokio::task::spawn(async move {
+                handle_connection(stream, seats, connected, event_sender, order_sender, snapshot_receiver).await;
+            });
+        }
+    }
+}
+
+
+
+pub fn to_server_message(event: Event, name: &PlayerName) -> Option<ServerMessage> {
+    // None when the event isn't this bot's business
+    let message = match event {
+        Event::Update(update) => ServerMessage::Update(update),
+        Event::DealCards(players_inventory) => {
+            let hand = players_inventory.get(name).copied().unwrap_or_default();
+            ServerMessage::Deal { hand, players: players_inventory.keys().cloned().collect() }
+        },
+        Event::EndRound => ServerMessage::EndRound,
+        Event::Ack(ack) if ack.player_name == *name => ServerMessage::Ack(ack),
+        Event::Ack(_) => return None,
+    };
+    Some(message)
+}
+
+pub fn current_deal(snapshot: &Snapshot, name: &PlayerName) -> Option<ServerMessage> {
+    // None between rounds, hands are only dealt for the round in progress (`round_start` is cleared while they're being dealt)
+    if !snapshot.trading && snapshot.round_start.is_some() {
+        return None;
+    }
+    let hand = snapshot.inventories.get(name).copied()?;
+    Some(ServerMessage::Deal { hand, players: snapshot.player_names.clone() })
+}
+
+pub fn to_request(message: ClientMessage, name: &PlayerName) -> Option<Request> {
+    match message {
+        ClientMessage::Login { .. } => None,
+        ClientMessage::Order { card, direction, price } => Some(Request::Order(Order { player_name: name.clone(), price, direction, card })),
+        ClientMessage::Cancel { card, direction } => Some(Request::Cancel(Cancel { player_name: name.clone(), direction, card })),
+    }
+}
+
+pub async fn send_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &ServerMessage) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(message).unwrap_or_default();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    seats: Vec<PlayerName>,
+    connected: Arc<Mutex<HashSet<PlayerName>>>,
+    event_sender: Sender<Event>,
+    order_sender: Arc<AsyncSender<Request>>,
+    snapshot_receiver: watch::Receiver<Snapshot>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    // =-= Login =-= //
+    let login = match lines.next_line().await {
+        Ok(Some(line)) => match serde_json::from_str::<ClientMessage>(&line) {
+            Ok(ClientMessage::Login { name }) => name,
+            _ => {
+                let _ = send_message(&mut writer, &ServerMessage::Error { message: String::from("expected a login message first") }).await;
+                return;
+            }
+        },
+        _ => return,
+    };
+
+    let name = PlayerName::from(login.clone());
+    if name.is_reserved() {
+        let _ = send_message(&mut writer, &ServerMessage::Error { message: format!("{} is a reserved name, log in with your seat's name", login) }).await;
+        return;
+    }
+    if !seats.contains(&name) {
+        let _ = send_message(&mut writer, &ServerMessage::Error { message: format!("there's no seat for {} at this table", login) }).await;
+        return;
+    }
+    if !connected.lock().await.insert(name.clone()) {
+        let _ = send_message(&mut writer, &ServerMessage::Error { message: format!("{} is already connected", login) }).await;
+        return;
+    }
+    if send_message(&mut writer, &ServerMessage::Welcome { name: name.clone() }).await.is_err() {
+        connected.lock().await.remove(&name);
+        return;
+    }
+    info!(player = %name, "took their seat");
+
+
+    // =-= Events -> Bot =-= //
+    let mut event_receiver = event_sender.subscribe();
+
+    // the deal went out before we were listening, the snapshot is published just ahead of it, so a bot gets its hand at least once
+    // (twice if it lands in between, the same hand both times)
+    let deal = current_deal(&snapshot_receiver.borrow(), &name);
+    if let Some(deal) = deal {
+        if let Err(e) = send_message(&mut writer, &deal).await {
+            warn!(player = %name, error = %e, "unable to send the current deal");
+            connected.lock().await.remove(&name);
+            return;
+        }
+    }
+    let writer_name = name.clone();
+    let writer = Arc::new(Mutex::new(writer));
+    let event_writer = writer.clone();
+    let forward_events = tokio::task::spawn(async move {
+        loop {
+            let message = match event_receiver.recv().await {
+                Ok(event) => match to_server_message(event, &writer_name) {
+                    Some(message) => message,
+                    None => continue,
+                },
+                Err(RecvError::Lagged(skipped)) => ServerMessage::Error { message: format!("lagged behind by {} events", skipped) },
+                Err(RecvError::Closed) => break,
+            };
+            let mut writer: tokio::sync::MutexGuard<OwnedWriteHalf> = event_writer.lock().await;
+            if send_message(&mut *writer, &message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+
+    // =-= Bot -> Match Maker =-= //
+    while let Ok(Some(line)) = lines.next_line().await {
+        let request = match serde_json::from_str::<ClientMessage>(&line) {
+            Ok(message) => to_request(message, &name),
+            Err(e) => {
+                let _ = send_message(&mut *writer.lock().await, &ServerMessage::Error { message: format!("unable to parse message: {}", e) }).await;
+                continue;
+            }
+        };
+
+        if let Some(request) = request {
+            if let Err(e) = order_sender.send(request).await {
+                error!(player = %name, error = %e, "unable to send order");
+                break;
+            }
+        }
+    }
+
+    forward_events.abort();
+
+    // nobody is left to manage its quotes, so the match maker pulls them before the seat is freed up, like a subprocess bot sitting out
+    if let Err(e) = order_sender.send(Request::Disconnect(name.clone())).await {
+        error!(player = %name, error = %e, "unable to pull the quotes of a bot that left");
+    }
+    connected.lock().await.remove(&name);
+    info!(player = %name, "left their seat");
+}
@@ -0,0 +1,37 @@
+use super::{Card, Direction, Inventory, Update, Ack, PlayerName};
+use serde::{Serialize, Deserialize};
+
+
+// =-= Protocol =-= //
+// - one JSON object per line, both ways, tagged with "type"
+// - bots only ever see their own hand, everyone else's stays hidden like it would at a real table
+//
+// -> {"type":"login","name":"alice"}
+// <- {"type":"welcome","name":"alice"}
+// <- {"type":"deal","hand":{"spades":3,"clubs":2,"diamonds":1,"hearts":2},"players":["TiltInventory","alice",...]}
+// <- {"type":"update","spades":{...},"clubs":{...},"diamonds":{...},"hearts":{...},"trade":null}
+// -> {"type":"order","card":"Spade","direction":"Buy","price":7}
+// -> {"type":"cancel","card":"Spade","direction":"Buy"}
+// <- {"type":"ack","player_name":"alice","seq":42,"timestamp":1250000,"card":"Spade","direction":"Buy","price":7,"status":"resting"}
+// <- {"type":"ack",...,"status":{"rejected":"rate_limited"}}
+// <- {"type":"end_round"}
+// <- {"type":"error","message":"..."}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Login { name: String },
+    Order { card: Card, direction: Direction, price: usize },
+    Cancel { card: Card, direction: Direction },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome { name: PlayerName },
+    Deal { hand: Inventory, players: Vec<PlayerName> },
+    Update(Box<Update>),
+    EndRound,
+    Ack(Ack), // one for every request we sent, never anyone else's
+    Error { message: String },
+}
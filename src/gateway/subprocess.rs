@@ -0,0 +1,174 @@
+use super::{Request, Event, PlayerName};
+use super::{ClientMessage, ServerMessage, send_message, to_request, to_server_message};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::broadcast::Sender;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{timeout, Duration};
+use kanal::AsyncSender;
+use std::process::Stdio;
+use std::sync::Arc;
+use tracing::{info, warn, error};
+
+
+// =-= SubprocessBot =-= //
+// - the lighter alternative to the gateway: we start the bot ourselves and talk to it over stdin/stdout
+// - same JSON lines as the gateway, except we open with the welcome and the bot answers with a login once it's ready
+// - the bot can log to its stderr, every line is passed on through our logging so it doesn't draw over the dashboard
+// - if it doesn't get ready in time, stops reading its stdin, crashes or exits, its quotes are pulled and it sits out
+// the rest of the game (its cards stay where they are)
+
+pub struct SubprocessBot {
+    pub name: PlayerName,
+    pub command: Vec<String>,
+    pub startup_timeout: Duration,
+    pub write_timeout: Duration,
+    pub event_sender: Sender<Event>,
+    pub order_sender: Arc<AsyncSender<Request>>,
+}
+
+impl SubprocessBot {
+    pub fn new(
+        name: PlayerName,
+        command: Vec<String>,
+        event_sender: Sender<Event>,
+        order_sender: Arc<AsyncSender<Request>>,
+    ) -> Self {
+        Self {
+            name,
+            command,
+            startup_timeout: Duration::from_secs(5),
+            write_timeout: Duration::from_secs(1),
+            event_sender,
+            order_sender,
+        }
+    }
+
+
+
+    pub async fn start(&mut self) {
+        let Some((program, args)) = self.command.split_first() else {
+            error!(player = %self.name, "no command to run");
+            return;
+        };
+
+        let spawned = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+        let mut child: Child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                error!(player = %self.name, command = ?self.command, error = %e, "unable to start the bot");
+                return;
+            }
+        };
+        let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            self.sit_out(&mut child, "no stdin/stdout to talk over").await;
+            return;
+        };
+        let mut lines = BufReader::new(stdout).lines();
+        if let Some(stderr) = child.stderr.take() {
+            let name = self.name.clone();
+            tokio::task::spawn(async move {
+                // ends with the bot, once its end of the pipe is closed
+                let mut stderr = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = stderr.next_line().await {
+                    warn!(player = %name, line = %line, "bot stderr");
+                }
+            });
+        }
+
+        // subscribe before the handshake so the deal can't slip past us while the bot is booting
+        let mut event_receiver = self.event_sender.subscribe();
+
+
+        // =-= Handshake =-= //
+        if !self.write(&mut stdin, &ServerMessage::Welcome { name: self.name.clone() }).await {
+            self.sit_out(&mut child, "unable to send the welcome").await;
+            return;
+        }
+        match timeout(self.startup_timeout, lines.next_line()).await {
+            Ok(Ok(Some(line))) if matches!(serde_json::from_str::<ClientMessage>(&line), Ok(ClientMessage::Login { .. })) => {
+                info!(player = %self.name, command = ?self.command, "bot is ready");
+            },
+            Ok(Ok(Some(_))) => {
+                self.sit_out(&mut child, "expected a login message first").await;
+                return;
+            },
+            Ok(_) => {
+                self.sit_out(&mut child, "exited before logging in").await;
+                return;
+            },
+            Err(_) => {
+                self.sit_out(&mut child, "didn't log in in time").await;
+                return;
+            },
+        }
+
+
+        // =-= Relay =-= //
+        let reason = loop {
+            tokio::select! {
+                event = event_receiver.recv() => {
+                    let message = match event {
+                        Ok(event) => match to_server_message(event, &self.name) {
+                            Some(message) => message,
+                            None => continue,
+                        },
+                        Err(RecvError::Lagged(skipped)) => ServerMessage::Error { message: format!("lagged behind by {} events", skipped) },
+                        Err(RecvError::Closed) => break "the match maker is gone",
+                    };
+                    if !self.write(&mut stdin, &message).await {
+                        break "stopped reading its stdin";
+                    }
+                },
+                line = lines.next_line() => {
+                    let line = match line {
+                        Ok(Some(line)) => line,
+                        _ => break "closed its stdout",
+                    };
+                    match serde_json::from_str::<ClientMessage>(&line) {
+                        Ok(message) => {
+                            if let Some(request) = to_request(message, &self.name) {
+                                if let Err(e) = self.order_sender.send(request).await {
+                                    error!(player = %self.name, error = %e, "unable to send order");
+                                    break "the match maker is gone";
+                                }
+                            }
+                        },
+                        Err(e) => {
+                            let message = ServerMessage::Error { message: format!("unable to parse message: {}", e) };
+                            if !self.write(&mut stdin, &message).await {
+                                break "stopped reading its stdin";
+                            }
+                        },
+                    }
+                },
+                status = child.wait() => {
+                    warn!(player = %self.name, status = ?status, "bot exited");
+                    break "exited";
+                },
+            }
+        };
+
+        self.sit_out(&mut child, reason).await;
+    }
+
+    pub async fn write(&self, stdin: &mut ChildStdin, message: &ServerMessage) -> bool {
+        matches!(timeout(self.write_timeout, send_message(stdin, message)).await, Ok(Ok(())))
+    }
+
+    pub async fn sit_out(&self, child: &mut Child, reason: &str) {
+        warn!(player = %self.name, reason, "sitting the bot out");
+        let _ = child.kill().await;
+
+        // nobody is left to manage its quotes, so they shouldn't be left on the book to get picked off
+        if let Err(e) = self.order_sender.send(Request::Disconnect(self.name.clone())).await {
+            error!(player = %self.name, error = %e, "unable to pull the quotes of a bot that sat out");
+        }
+    }
+}
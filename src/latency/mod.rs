@@ -0,0 +1,198 @@
+use super::{Request, Event, EVENT_CAPACITY};
+use tokio::sync::broadcast::{self, Sender};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{warn, error};
+use kanal::AsyncSender;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::str::FromStr;
+use std::sync::Arc;
+
+
+// =-= Latency =-= //
+// - everyone shares one in-process channel by default, so whoever reacts fastest (PickOff) wins every race
+// - a player with a latency profile gets its own link instead: orders it sends and events it receives are held back
+// by a base delay plus some jitter before they're passed along
+// - messages keep their order on the way through, like they would over a TCP connection, jitter only ever stretches
+// the gap between them
+// - written as `<base>[+<jitter>(<amount>)]`, e.g. `20ms`, `5ms+uniform(10ms)`, `50ms+normal(15ms)`, `1ms+exp(4ms)`
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jitter {
+    None,
+    Uniform(Duration), // anywhere between 0 and the amount
+    Normal(Duration), // standard deviation around the base
+    Exponential(Duration), // mean of a long tail on top of the base
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Latency {
+    pub base: Duration,
+    pub jitter: Jitter,
+}
+
+impl Latency {
+    pub fn new(base: Duration, jitter: Jitter) -> Self {
+        Self { base, jitter }
+    }
+
+    pub fn sample(&self, rng: &mut StdRng) -> Duration {
+        let base = self.base.as_secs_f64();
+        let delay = match self.jitter {
+            Jitter::None => base,
+            Jitter::Uniform(amount) => base + rng.gen_range(0.0..=1.0) * amount.as_secs_f64(),
+            Jitter::Normal(std_dev) => {
+                // Box-Muller, clamped at zero since nothing arrives before it was sent
+                let (u1, u2): (f64, f64) = (rng.gen_range(f64::EPSILON..1.0), rng.gen_range(0.0..1.0));
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                base + z * std_dev.as_secs_f64()
+            },
+            Jitter::Exponential(mean) => base - rng.gen_range(f64::EPSILON..1.0_f64).ln() * mean.as_secs_f64(),
+        };
+        Duration::from_secs_f64(delay.max(0.0))
+    }
+}
+
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, scale) = if let Some(number) = text.strip_suffix("us") {
+        (number, 1e-6)
+    } else if let Some(number) = text.strip_suffix("ms") {
+        (number, 1e-3)
+    } else if let Some(number) = text.strip_suffix('s') {
+        (number, 1.0)
+    } else {
+        (text, 1e-3) // plain numbers are milliseconds
+    };
+    match number.trim().parse::<f64>() {
+        Ok(value) if value >= 0.0 => Ok(Duration::from_secs_f64(value * scale)),
+        _ => Err(format!("'{}' isn't a duration, try something like 20ms", text)),
+    }
+}
+
+impl FromStr for Latency {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (base, jitter) = match text.split_once('+') {
+            Some((base, jitter)) => (base, Some(jitter)),
+            None => (text, None),
+        };
+        let base = parse_duration(base)?;
+
+        let jitter = match jitter {
+            None => Jitter::None,
+            Some(jitter) => {
+                let (kind, amount) = jitter.trim().trim_end_matches(')').split_once('(').ok_or(format!("'{}' should look like uniform(10ms)", jitter))?;
+                let amount = parse_duration(amount)?;
+                match kind {
+                    "uniform" => Jitter::Uniform(amount),
+                    "normal" => Jitter::Normal(amount),
+                    "exp" => Jitter::Exponential(amount),
+                    _ => return Err(format!("unknown jitter '{}', pick from uniform, normal or exp", kind)),
+                }
+            },
+        };
+        Ok(Latency::new(base, jitter))
+    }
+}
+
+
+// =-= LatencyProfile =-= //
+// - inbound is match maker -> player (events), outbound is player -> match maker (orders)
+// - `--latency PickOff=20ms` uses the same delay both ways, `--latency PickOff=5ms,50ms+normal(10ms)` splits them
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyProfile {
+    pub inbound: Latency,
+    pub outbound: Latency,
+}
+
+impl FromStr for LatencyProfile {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.split_once(',') {
+            Some((inbound, outbound)) => Ok(Self { inbound: inbound.parse()?, outbound: outbound.parse()? }),
+            None => {
+                let latency: Latency = text.parse()?;
+                Ok(Self { inbound: latency, outbound: latency })
+            },
+        }
+    }
+}
+
+impl LatencyProfile {
+    pub fn link(&self, event_sender: &Sender<Event>, order_sender: &Arc<AsyncSender<Request>>) -> (Sender<Event>, Arc<AsyncSender<Request>>) {
+        // the player is handed these instead of the real thing, and can't tell the difference
+        (delay_events(self.inbound, event_sender), delay_orders(self.outbound, Arc::clone(order_sender)))
+    }
+}
+
+
+
+pub fn delay_events(latency: Latency, event_sender: &Sender<Event>) -> Sender<Event> {
+    let (delayed_sender, _) = broadcast::channel::<Event>(EVENT_CAPACITY);
+    let mut event_receiver = event_sender.subscribe();
+    let (stamped_sender, mut stamped_receiver) = mpsc::unbounded_channel::<(Instant, Event)>();
+
+    // stamp each event the moment it's published, so time spent waiting on the one ahead of it still counts
+    tokio::task::spawn(async move {
+        let mut rng = StdRng::from_entropy();
+        loop {
+            let event = match event_receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "event link lagged behind");
+                    continue;
+                },
+                Err(RecvError::Closed) => break,
+            };
+            if stamped_sender.send((Instant::now() + latency.sample(&mut rng), event)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let relay_sender = delayed_sender.clone();
+    tokio::task::spawn(async move {
+        let mut last_delivery = Instant::now();
+        while let Some((deliver_at, event)) = stamped_receiver.recv().await {
+            last_delivery = last_delivery.max(deliver_at);
+            sleep_until(last_delivery).await;
+            let _ = relay_sender.send(event); // nobody subscribed yet is the same as missing it on the real channel
+        }
+    });
+
+    delayed_sender
+}
+
+pub fn delay_orders(latency: Latency, order_sender: Arc<AsyncSender<Request>>) -> Arc<AsyncSender<Request>> {
+    let (delayed_sender, delayed_receiver) = kanal::unbounded_async::<Request>();
+    let (stamped_sender, mut stamped_receiver) = mpsc::unbounded_channel::<(Instant, Request)>();
+
+    tokio::task::spawn(async move {
+        let mut rng = StdRng::from_entropy();
+        while let Ok(request) = delayed_receiver.recv().await {
+            if stamped_sender.send((Instant::now() + latency.sample(&mut rng), request)).is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::task::spawn(async move {
+        let mut last_delivery = Instant::now();
+        while let Some((deliver_at, request)) = stamped_receiver.recv().await {
+            last_delivery = last_delivery.max(deliver_at);
+            sleep_until(last_delivery).await;
+            if let Err(e) = order_sender.send(request).await {
+                error!(error = %e, "unable to forward order");
+                break;
+            }
+        }
+    });
+
+    Arc::new(delayed_sender)
+}
@@ -0,0 +1,34 @@
+#[macro_use]
+pub mod utils;
+pub use utils::*;
+
+pub mod logging;
+
+pub mod models;
+pub use models::*;
+
+pub mod match_maker;
+pub use match_maker::{MatchMaker, ThrottleConfig, ThrottleAction, SelfTradePrevention, Checkpoint, Metrics, EVENT_CAPACITY};
+
+pub mod tui;
+
+pub mod gateway;
+
+pub mod spectator;
+
+pub mod latency;
+
+pub mod analytics;
+
+pub mod rating;
+
+#[cfg(feature = "batch")]
+pub mod batch;
+#[cfg(feature = "batch")]
+pub mod tuning;
+pub mod report;
+
+pub mod player;
+pub use player::PlayerName;
+pub use player::generic::GenericPlayer;
+pub use player::event_driven::EventDrivenPlayer;
@@ -0,0 +1,110 @@
+use super::NO_COLOR;
+use tracing::Metadata;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+
+// =-= Logging =-= //
+// - diagnostics (rejections, fills, lag, connections, errors, ...) go through `tracing`, with what they're about as fields
+// (round, player, suit, price, ...) instead of baked into the text
+// - the running commentary of a game (books, hands, results, reports, leaderboards) goes through `narrate!`, which draws it for
+// people while `console!` is showing and otherwise logs it as events marked `narrative`, so each line has exactly one sink
+// - `--log warn,match_maker=debug,player=off` sets a level for everything and then per component (the crate's modules)
+// - `--log-format json` writes a JSON object per line and silences `console!`, so stdout can be parsed as it comes
+// - `--no-color` (or NO_COLOR in the environment) drops the colors from both
+// - while a full-screen view has the terminal (see `tui::Screen`) log lines are dropped, they'd draw over it
+
+pub static HELD: AtomicBool = AtomicBool::new(false);
+
+pub const COMPONENTS: [&str; 10] = ["match_maker", "player", "gateway", "spectator", "latency", "analytics", "batch", "tuning", "rating", "tui"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("unknown format '{}', pick from text or json", format)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub level: LevelFilter,
+    pub components: Vec<(String, LevelFilter)>,
+    pub format: LogFormat,
+    pub color: bool,
+}
+
+impl LogConfig {
+    pub fn new(level: LevelFilter) -> Self {
+        Self { level, components: Vec::new(), format: LogFormat::Text, color: true }
+    }
+
+    pub fn parse_levels(&mut self, levels: &str) -> Result<(), String> {
+        // "warn,match_maker=debug", a bare level is for everything
+        let level = |level: &str| LevelFilter::from_str(level.trim())
+            .map_err(|_| format!("unknown level '{}', pick from off, error, warn, info, debug or trace", level.trim()));
+        for directive in levels.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((component, component_level)) => {
+                    let component = component.trim();
+                    if !COMPONENTS.contains(&component) {
+                        return Err(format!("unknown component '{}', pick from {}", component, COMPONENTS.join(", ")));
+                    }
+                    let component_level = level(component_level)?;
+                    self.components.retain(|(name, _)| name != component);
+                    self.components.push((component.to_string(), component_level));
+                },
+                None => self.level = level(directive)?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn targets(&self) -> Targets {
+        let crate_name = env!("CARGO_PKG_NAME").replace('-', "_");
+        self.components.iter().fold(Targets::new().with_default(self.level), |targets, (component, level)| {
+            targets.with_target(format!("{}::{}", crate_name, component), *level)
+        })
+    }
+}
+
+pub fn is_narrative(metadata: &Metadata) -> bool {
+    metadata.fields().field("narrative").is_some()
+}
+
+fn writer() -> Box<dyn Write> {
+    if HELD.load(Ordering::Relaxed) {
+        Box::new(io::sink())
+    } else {
+        Box::new(io::stdout())
+    }
+}
+
+pub fn init(config: &LogConfig) {
+    // once per process, anything after the first is ignored (e.g. a second game in the same test binary)
+    NO_COLOR.store(!config.color, Ordering::Relaxed);
+    let registry = tracing_subscriber::registry().with(config.targets());
+    let result = match config.format {
+        LogFormat::Text => {
+            registry.with(tracing_subscriber::fmt::layer().with_ansi(config.color).with_writer(writer)).try_init()
+        },
+        LogFormat::Json => registry.with(tracing_subscriber::fmt::layer().json().flatten_event(true).with_writer(writer)).try_init(),
+    };
+    if let Err(e) = result {
+        console!("[!] Unable to start logging: {}", e);
+    }
+}
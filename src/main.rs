@@ -1,25 +1,307 @@
 use std::sync::Arc;
+use std::io::IsTerminal;
+
+use figgie_auto::*;
+use figgie_auto::player::spawn_player;
+use figgie_auto::gateway::{Gateway, SubprocessBot};
+use figgie_auto::spectator::Spectator;
+use figgie_auto::tui::Dashboard;
+use figgie_auto::latency::LatencyProfile;
+use figgie_auto::analytics::{Analytics, Tables};
+#[cfg(feature = "batch")]
+use figgie_auto::batch::{BatchConfig, RateBy, run_batch, run_duplicate};
+#[cfg(feature = "batch")]
+use figgie_auto::rating::Ratings;
+#[cfg(feature = "batch")]
+use figgie_auto::tuning::{TuneConfig, Search, run_tuning};
+use figgie_auto::report::write_report;
+use figgie_auto::logging::{self, LogConfig, LogFormat};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use tracing::level_filters::LevelFilter;
+use tracing::{warn, error};
 
-pub mod utils;
-pub use utils::*;
 
-pub mod models;
-pub use models::*;
-
-pub mod match_maker;
-pub use match_maker::MatchMaker;
+fn main() {
 
-pub mod player;
-pub use player::PlayerName;
-pub use player::generic::GenericPlayer;
-pub use player::event_driven::EventDrivenPlayer;
+    const STARTING_BALANCE: usize = 500;
 
-use crate::player::TiltInventory;
+    let args: Vec<String> = std::env::args().collect();
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let value = |name: &str| args.iter().position(|arg| arg == name).and_then(|idx| args.get(idx + 1)).cloned();
+
+    // `--log warn,match_maker=debug` sets the diagnostic levels, `--log-format json` and `--no-color` how they're written, see `logging/mod.rs`
+    // headless runs (`--games`, `--tune`) only want to hear about trouble by default
+    let headless = value("--games").is_some() || value("--tune").is_some();
+    let mut log_config = LogConfig::new(if headless { LevelFilter::WARN } else { LevelFilter::INFO });
+    log_config.color = !flag("--no-color") && std::env::var_os("NO_COLOR").is_none();
+    let mut log_errors = Vec::new();
+    if let Some(levels) = value("--log") {
+        if let Err(e) = log_config.parse_levels(&levels) {
+            log_errors.push(format!("ignoring --log: {}", e));
+        }
+    }
+    match value("--log-format").map(|format| format.parse::<LogFormat>()) {
+        Some(Ok(format)) => log_config.format = format,
+        Some(Err(e)) => log_errors.push(format!("ignoring --log-format: {}", e)),
+        None => (),
+    }
+    // JSON lines are for machines, so nothing else gets written around them
+    let json_logs = log_config.format == LogFormat::Json;
+    if json_logs {
+        QUIET.store(true, Ordering::Relaxed);
+    }
+    logging::init(&log_config);
+    for e in log_errors {
+        warn!("{}", e);
+    }
+
+    // `--lineup TiltInventory,Spread,alice` picks the seats, any name that isn't one of our bots is an external seat
+    let mut players: Vec<PlayerName> = match value("--lineup") {
+        Some(lineup) => lineup.split(',').map(|name| PlayerName::from(name.trim().to_string())).collect(),
+        None => vec![PlayerName::TiltInventory, PlayerName::Spread, PlayerName::Seller, PlayerName::Noisy, PlayerName::PickOff],
+    };
+
+    // `--human` gives our seat to the Noisy player, so we're playing against the rest of the bots
+    let human = flag("--human");
+    if human {
+        match players.iter().position(|player_name| *player_name == PlayerName::Noisy) {
+            Some(idx) => players[idx] = PlayerName::Human,
+            None => players.push(PlayerName::Human),
+        }
+    }
+
+    // the dashboard takes over the terminal when there is one, `--no-dashboard` keeps the old scrolling output
+    let dashboard = !human && !json_logs && std::io::stdout().is_terminal() && !flag("--no-dashboard");
+    let hide_hands = flag("--hide-hands");
+
+    // external seats connect through the gateway, see `gateway/protocol.rs`
+    let gateway_address = value("--gateway").unwrap_or(String::from("127.0.0.1:7878"));
+
+    // `--spectate 127.0.0.1:8080` serves a page to watch from a browser, plus the JSON and event stream behind it, see `spectator/mod.rs`
+    // it also serves `/metrics` for Prometheus to scrape
+    let spectator_address = value("--spectate");
+
+    // `--bot alice="python3 bot.py"` has us run alice ourselves over stdin/stdout instead of waiting on the gateway
+    let bots: Vec<(PlayerName, Vec<String>)> = args.iter().enumerate()
+        .filter(|(_, arg)| *arg == "--bot")
+        .filter_map(|(idx, _)| args.get(idx + 1))
+        .filter_map(|bot| bot.split_once('='))
+        .map(|(name, command)| (PlayerName::from(name.trim().to_string()), command.split_whitespace().map(String::from).collect()))
+        .collect();
+    for (player_name, _) in bots.iter() {
+        if !players.contains(player_name) {
+            players.push(player_name.clone());
+        }
+    }
+
+    if let Some(player_name) = players.iter().find(|player_name| player_name.is_reserved()) {
+        error!(player = %player_name, "is a reserved name, it marks an empty side of the book, pick another name for that seat");
+        return;
+    }
+
+    // every seat is keyed by its name (points, hands, quotes, ratings), so the same name twice would share one seat's books
+    let mut seen: Vec<&PlayerName> = Vec::new();
+    for player_name in players.iter() {
+        if seen.contains(&player_name) {
+            error!(player = %player_name, "is in the lineup more than once, every seat needs its own name");
+            return;
+        }
+        seen.push(player_name);
+    }
+
+    // a mistyped bot name would otherwise quietly become a gateway seat that nobody ever connects to
+    let our_bots = [PlayerName::Spread, PlayerName::Seller, PlayerName::Taker, PlayerName::Noisy, PlayerName::WildestDreams, PlayerName::PickOff, PlayerName::TiltInventory, PlayerName::TheHoarder, PlayerName::PrayingMantis];
+    for player_name in players.iter().filter(|player_name| matches!(player_name, PlayerName::External(_))) {
+        if bots.iter().any(|(bot_name, _)| bot_name == player_name) {
+            continue;
+        }
+        match our_bots.iter().find(|bot| bot.to_string().eq_ignore_ascii_case(&player_name.to_string())) {
+            Some(bot) => warn!(player = %player_name, "isn't one of our bots, did you mean {}? it's left as an external seat", bot),
+            None => warn!(player = %player_name, "isn't one of our bots, it's left as an external seat for the gateway"),
+        }
+    }
+
+    // `--latency PickOff=20ms` or `--latency PickOff=5ms,50ms+normal(10ms)` slows a seat down (inbound,outbound), `*` means everyone
+    // gateway seats are left alone, they already pay for a real network
+    let mut latencies: HashMap<PlayerName, LatencyProfile> = HashMap::new();
+    for (idx, _) in args.iter().enumerate().filter(|(_, arg)| *arg == "--latency") {
+        let Some((name, spec)) = args.get(idx + 1).and_then(|latency| latency.split_once('=')) else { continue };
+        let profile: LatencyProfile = match spec.parse() {
+            Ok(profile) => profile,
+            Err(e) => {
+                warn!(player = name, "ignoring --latency: {}", e);
+                continue;
+            }
+        };
+        if name == "*" {
+            let gateway_seat = |player_name: &PlayerName| matches!(player_name, PlayerName::External(_)) && !bots.iter().any(|(bot_name, _)| bot_name == player_name);
+            for player_name in players.iter().filter(|player_name| !gateway_seat(player_name)) {
+                latencies.entry(player_name.clone()).or_insert(profile);
+            }
+        } else {
+            latencies.insert(PlayerName::from(name.trim().to_string()), profile);
+        }
+    }
+
+    // per-player limits the match maker enforces, `--throttle delay` holds excess orders back instead of rejecting them
+    // both are off unless asked for, and a rate of 0 would hold every order back forever so it's refused
+    let mut throttle = ThrottleConfig::default();
+    if let Some(rate) = value("--max-rate") {
+        match rate.parse::<f64>() {
+            Ok(rate) if rate.is_finite() && rate > 0.0 => {
+                throttle.orders_per_second = rate;
+                throttle.burst = rate.max(1.0);
+            },
+            _ => warn!("ignoring --max-rate: '{}' isn't a rate above 0", rate),
+        }
+    }
+    if let Some(max_outstanding) = value("--max-outstanding") {
+        match max_outstanding.parse::<usize>() {
+            Ok(max_outstanding) if max_outstanding > 0 => throttle.max_outstanding = max_outstanding,
+            _ => warn!("ignoring --max-outstanding: '{}' isn't a count above 0", max_outstanding),
+        }
+    }
+    if value("--throttle").as_deref() == Some("delay") {
+        throttle.action = ThrottleAction::Delay;
+    }
+
+    // `--batch 50ms` collects orders for that long and runs them in a random order, so raw speed inside the window counts for nothing
+    let batch_window = value("--batch").and_then(|window| match figgie_auto::latency::parse_duration(&window) {
+        Ok(window) => Some(window),
+        Err(e) => {
+            warn!("ignoring --batch: {}", e);
+            None
+        }
+    });
 
+    // `--stp cancel-resting` picks how the match maker stops players trading with themselves, see `match_maker/self_trade.rs`
+    let self_trade_prevention = match value("--stp").map(|mode| mode.parse::<SelfTradePrevention>()) {
+        Some(Ok(mode)) => mode,
+        Some(Err(e)) => {
+            warn!("ignoring --stp: {}", e);
+            SelfTradePrevention::Reject
+        },
+        None => SelfTradePrevention::Reject,
+    };
+
+    // `--audit` re-checks cards, points and quotes after every trade and at settlement, see `match_maker/audit.rs`
+    let audit_mode = flag("--audit");
+
+    // `--report reports/` writes each round's PnL breakdown to `rounds.jsonl` and the running totals to `session.json`
+    let report_dir = value("--report").map(std::path::PathBuf::from);
+
+    // `--export tables/` writes the session's trades, quotes, deals and results as CSV, see `analytics/tables.rs`
+    let export_dir = value("--export").map(std::path::PathBuf::from);
+
+    // `--html tables/` turns a session recorded with `--export tables/` into an HTML report (`--out` picks where), see `report/mod.rs`
+    if let Some(dir) = value("--html") {
+        match write_report(std::path::Path::new(&dir), value("--out").map(std::path::PathBuf::from)) {
+            Ok(path) => console!("{}[+] Report written to {:?}{}", CL::DimLightBlue.get(), path, CL::End.get()),
+            Err(e) => error!(dir, error = %e, "unable to write the report"),
+        }
+        return;
+    }
+
+    // headless runs play on tokio's paused clock, which only comes with the `batch` feature
+    #[cfg(not(feature = "batch"))]
+    if headless {
+        error!("--games and --tune need the batch feature, build with `cargo build --features batch`");
+        return;
+    }
+
+    // `--tune Seller` searches Seller's parameters over headless games against the rest of the lineup, see `tuning/mod.rs`
+    // `--tune-params a,b` narrows the search, `--search grid|genetic`, `--games` per candidate, `--tune-out` saves the best set
+    #[cfg(feature = "batch")]
+    if let Some(player) = value("--tune") {
+        let lineup: Vec<PlayerName> = players.iter()
+            .filter(|player_name| !matches!(player_name, PlayerName::External(_) | PlayerName::Human))
+            .cloned().collect();
+        let mut batch = BatchConfig::new(value("--games").and_then(|games| games.parse::<usize>().ok()).unwrap_or(8).max(1), lineup);
+        batch.starting_balance = STARTING_BALANCE;
+        batch.rounds = value("--rounds").and_then(|rounds| rounds.parse::<u32>().ok()).unwrap_or(3).max(1);
+        batch.seed = value("--seed").and_then(|seed| seed.parse::<u64>().ok());
+
+        let mut config = TuneConfig::new(batch, PlayerName::from(player));
+        if let Some(params) = value("--tune-params") {
+            config.params = params.split(',').map(|name| name.trim().to_string()).collect();
+        }
+        match value("--search").map(|search| search.parse::<Search>()) {
+            Some(Ok(search)) => config.search = search,
+            Some(Err(e)) => warn!("ignoring --search: {}", e),
+            None => {},
+        }
+        if let Some(population) = value("--population").and_then(|population| population.parse::<usize>().ok()) {
+            config.population = population;
+        }
+        if let Some(generations) = value("--generations").and_then(|generations| generations.parse::<usize>().ok()) {
+            config.generations = generations;
+        }
+        if let Some(validation_games) = value("--validation-games").and_then(|games| games.parse::<usize>().ok()) {
+            config.validation_games = validation_games.max(2);
+        }
+        config.output = value("--tune-out").map(std::path::PathBuf::from);
 
-fn main() {
+        console!("{}[+] Tuning {:?} over {} games of {} rounds |:| {:?}{}", CL::DimLightBlue.get(), config.player, config.batch.games, config.batch.rounds, config.batch.lineup, CL::End.get());
+        if let Err(e) = run_tuning(&config) {
+            error!(error = %e, "unable to tune");
+        }
+        return;
+    }
+
+    // `--games 100` plays that many games back to back with nobody watching and rates the bots on how they place, see `batch/mod.rs`
+    // `--rounds` per game, `--seed` for repeatable deals, `--rate-by round|game`, `--ratings` is where the ratings are kept
+    // `--duplicate` makes each of those games a deal replayed with everyone rotated through every seat, see `batch/duplicate.rs`
+    #[cfg(feature = "batch")]
+    if let Some(games) = value("--games").and_then(|games| games.parse::<usize>().ok()) {
+        let lineup: Vec<PlayerName> = players.iter()
+            .filter(|player_name| !matches!(player_name, PlayerName::External(_) | PlayerName::Human))
+            .cloned().collect();
+        let mut config = BatchConfig::new(games, lineup);
+        config.starting_balance = STARTING_BALANCE;
+        if let Some(rounds) = value("--rounds").and_then(|rounds| rounds.parse::<u32>().ok()) {
+            config.rounds = rounds.max(1);
+        }
+        config.seed = value("--seed").and_then(|seed| seed.parse::<u64>().ok());
+        match value("--rate-by").map(|rate_by| rate_by.parse::<RateBy>()) {
+            Some(Ok(rate_by)) => config.rate_by = rate_by,
+            Some(Err(e)) => warn!("ignoring --rate-by: {}", e),
+            None => {},
+        }
+        let ratings_path = std::path::PathBuf::from(value("--ratings").unwrap_or(String::from("ratings.json")));
+        let mut ratings = match Ratings::load(&ratings_path) {
+            Ok(ratings) => ratings,
+            Err(e) => {
+                warn!(path = ?ratings_path, error = %e, "unable to load ratings, starting fresh");
+                Ratings::default()
+            }
+        };
+        config.ratings_path = Some(ratings_path);
+        config.export = export_dir;
+
+        // `--params seller.json` seats a set saved by `--tune-out` in place of that strategy's defaults, rated under its own label
+        for (idx, _) in args.iter().enumerate().filter(|(_, arg)| *arg == "--params") {
+            let Some(path) = args.get(idx + 1) else { continue };
+            let params = std::fs::read_to_string(path).map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str::<player::StrategyParams>(&text).map_err(|e| e.to_string()));
+            match params {
+                Ok(params) if config.lineup.contains(&params.player_name()) => {
+                    config.params.insert(params.player_name(), params);
+                },
+                Ok(params) => warn!(path = %path, "ignoring --params: {} isn't in the lineup", params.player_name()),
+                Err(e) => warn!(path = %path, "ignoring --params: {}", e),
+            }
+        }
 
-    const STARTING_BALANCE: usize = 500;
+        if flag("--duplicate") {
+            console!("{}[+] Playing {} duplicate deals of {} rounds, {} seats each |:| {:?}{}", CL::DimLightBlue.get(), config.games, config.rounds, config.lineup.len(), config.lineup, CL::End.get());
+            run_duplicate(&config, &mut ratings);
+        } else {
+            console!("{}[+] Playing {} games of {} rounds |:| {:?}{}", CL::DimLightBlue.get(), config.games, config.rounds, config.lineup, CL::End.get());
+            run_batch(&config, &mut ratings);
+        }
+        return;
+    }
 
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -27,107 +309,124 @@ fn main() {
         .expect("Failed to build runtime");
     runtime.block_on(async {
 
-        println!("");
-        println!("{}|==============================================|{}", CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}{}           Welcome to Figgie Auto!            {}{}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::Teal.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}         {}---------------------------{}          {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::Dull.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}    This is an algorithmic twist on Jane      {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}   Street's 'Figgie'. However, instead of     {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}  playing manually, setup some participants   {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}  and see how they interact with each other.  {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}       See 'player' for dev framework         {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}                                              {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|{}{}    -  All credit goes to Jane Street  -      {}{}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DullTeal.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
-        println!("{}|==============================================|{}\n", CL::DimLightBlue.get(), CL::End.get());
+        console!();
+        console!("{}|==============================================|{}", CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}{}           Welcome to Figgie Auto!            {}{}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::Teal.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}         {}---------------------------{}          {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::Dull.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}    This is an algorithmic twist on Jane      {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}   Street's 'Figgie'. However, instead of     {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}  playing manually, setup some participants   {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}  and see how they interact with each other.  {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}       See 'player' for dev framework         {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}                                              {}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|{}{}    -  All credit goes to Jane Street  -      {}{}|{}", CL::DimLightBlue.get(), CL::End.get(), CL::DullTeal.get(), CL::End.get(), CL::DimLightBlue.get(), CL::End.get());
+        console!("{}|==============================================|{}\n", CL::DimLightBlue.get(), CL::End.get());
 
-        println!("Let the games begin!\n");
+        console!("Let the games begin!\n");
 
 
         let mut handles = Vec::new();
 
-        let (tx, rx) = kanal::unbounded_async::<Order>();
+        let (tx, rx) = kanal::unbounded_async::<Request>();
         let match_maker_order_receiver = Arc::new(rx);
-        let player_1_order_sender = Arc::new(tx);
-        let player_2_order_sender = Arc::clone(&player_1_order_sender);
-        let player_3_order_sender = Arc::clone(&player_1_order_sender);
-        let player_4_order_sender = Arc::clone(&player_1_order_sender);
-        let player_5_order_sender = Arc::clone(&player_1_order_sender);
-
+        let order_sender = Arc::new(tx);
 
-        let (match_maker_event_sender, _) = tokio::sync::broadcast::channel::<Event>(100);
-        let player_1_event_receiver = match_maker_event_sender.clone();
-        let player_2_event_receiver = match_maker_event_sender.clone();
-        let player_3_event_receiver = match_maker_event_sender.clone();
-        let player_4_event_receiver = match_maker_event_sender.clone();
-        let player_5_event_receiver = match_maker_event_sender.clone();
+        let (match_maker_event_sender, _) = tokio::sync::broadcast::channel::<Event>(EVENT_CAPACITY);
 
 
-        let mut players = Vec::new();
-
+        // Players
+        let link = |player_name: &PlayerName| match latencies.get(player_name) {
+            Some(profile) => profile.link(&match_maker_event_sender, &order_sender),
+            None => (match_maker_event_sender.clone(), Arc::clone(&order_sender)),
+        };
+        for (player_name, profile) in latencies.iter() {
+            console!("{}[+] {:?} |:| Latency in: {:?} | out: {:?}{}", CL::Dull.get(), player_name, profile.inbound, profile.outbound, CL::End.get());
+        }
+        for player_name in players.iter() {
+            let (event_sender, player_order_sender) = link(player_name);
+            if let Some(player_handle) = spawn_player(player_name.clone(), None, event_sender, player_order_sender) {
+                handles.push(player_handle);
+            }
+        }
 
-        // Player 1
-        let player_name: PlayerName = PlayerName::TiltInventory;
-        players.push(player_name.clone());
-        let player_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
-            let mut player: TiltInventory = TiltInventory::new(player_name, false, 2000, 4000, player_1_event_receiver, player_1_order_sender);
-            player.start().await;
-        });
-        handles.push(player_handle);
 
+        // Subprocess bots
+        for (player_name, command) in bots.iter() {
+            let (event_sender, bot_order_sender) = link(player_name);
+            let mut bot: SubprocessBot = SubprocessBot::new(player_name.clone(), command.clone(), event_sender, bot_order_sender);
+            let bot_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
+                bot.start().await;
+            });
+            handles.push(bot_handle);
+        }
 
 
-        // Player 2
-        let player_name: PlayerName = PlayerName::Spread;
-        players.push(player_name.clone());
-        let player_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
-            let mut player: GenericPlayer = GenericPlayer::new(player_name, false, 1000, 2000, player_2_event_receiver, player_2_order_sender);
-            player.start().await;
-        });
-        handles.push(player_handle);
+        // the gateway needs the match maker's snapshots, so it's started further down
+        let external_seats: Vec<PlayerName> = players.iter()
+            .filter(|player_name| matches!(player_name, PlayerName::External(_)))
+            .filter(|player_name| !bots.iter().any(|(bot_name, _)| bot_name == *player_name))
+            .cloned().collect();
 
 
-        // Player 3
-        let player_name: PlayerName = PlayerName::Seller;
-        players.push(player_name.clone());
-        let player_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
-            let mut player: GenericPlayer = GenericPlayer::new(player_name, false, 2000, 4000, player_3_event_receiver, player_3_order_sender);
-            player.start().await;
+        // Matchmaker
+        let mut match_maker: MatchMaker = MatchMaker::new(STARTING_BALANCE, players, match_maker_event_sender, match_maker_order_receiver);
+        match_maker.throttle.config = throttle;
+        match_maker.batch_window = batch_window;
+        match_maker.self_trade_prevention = self_trade_prevention;
+        match_maker.audit_mode = audit_mode;
+        match_maker.analytics = Analytics::new(report_dir);
+        match_maker.tables = Tables::new(export_dir);
+        let snapshot_receiver = match_maker.snapshot_sender.subscribe();
+        let spectator_snapshot_receiver = match_maker.snapshot_sender.subscribe();
+        let spectator_event_sender = match_maker.event_sender.clone();
+        let gateway_snapshot_receiver = match_maker.snapshot_sender.subscribe();
+        let gateway_event_sender = match_maker.event_sender.clone();
+        let match_maker_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
+            match_maker.start().await;
         });
-        handles.push(player_handle);
-
+        handles.push(match_maker_handle);
 
-        // Player 4
-        let player_name: PlayerName = PlayerName::Noisy;
-        players.push(player_name.clone());
-        let player_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
-            let mut player: GenericPlayer = GenericPlayer::new(player_name, false, 4000, 8000, player_4_event_receiver, player_4_order_sender);
-            player.start().await;
-        });
-        handles.push(player_handle);
 
+        // Gateway
+        if !external_seats.is_empty() {
+            let mut gateway: Gateway = Gateway::new(gateway_address, external_seats, gateway_event_sender, Arc::clone(&order_sender), gateway_snapshot_receiver);
+            let gateway_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
+                gateway.start().await;
+            });
+            handles.push(gateway_handle);
+        }
 
-        // Player 5
-        let player_name: PlayerName = PlayerName::PickOff;
-        players.push(player_name.clone());
-        let player_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
-            let mut player: EventDrivenPlayer = EventDrivenPlayer::new(player_name, false, player_5_event_receiver, player_5_order_sender);
-            player.start().await;
-        });
-        handles.push(player_handle);
 
+        // Spectator
+        if let Some(address) = spectator_address {
+            let mut spectator: Spectator = Spectator::new(address, spectator_event_sender, spectator_snapshot_receiver);
+            let spectator_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
+                spectator.start().await;
+            });
+            handles.push(spectator_handle);
+        }
 
 
-        // Matchmaker
-        let match_maker_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
-            let mut match_maker: MatchMaker = MatchMaker::new(STARTING_BALANCE, players, match_maker_event_sender, match_maker_order_receiver);
-            match_maker.start().await;
-        });
-        handles.push(match_maker_handle);
+        // Dashboard
+        if dashboard {
+            let dashboard_handle: tokio::task::JoinHandle<()> = tokio::task::spawn(async move {
+                let mut dashboard: Dashboard = Dashboard::new(hide_hands, snapshot_receiver);
+                dashboard.start().await;
+            });
+            handles.push(dashboard_handle);
+        }
 
 
 
-        for handle in handles {
-            handle.await.unwrap();
+        // quitting a full-screen view ends the game, it's already handed the terminal back
+        let finished = async {
+            for handle in handles {
+                handle.await.unwrap();
+            }
+        };
+        tokio::select! {
+            _ = figgie_auto::tui::quit_requested() => {},
+            _ = finished => {},
         }
 
     });
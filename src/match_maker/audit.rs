@@ -0,0 +1,123 @@
+use super::{MatchMaker, Card, Inventory, PlayerName, CL};
+use tracing::error;
+
+
+// =-= Audit =-= //
+// - an opt-in mode (`--audit`) that re-checks the books after every trade and at settlement:
+// - cards: every card dealt is still in someone's hand, suit by suit
+// - points: everyone's points plus the pot add up to what the table started with
+// - pot: empty once the round is settled, an uneven tie's odd points included
+// - books: no resting ask without the card behind it, no resting bid without the points behind it
+// - a violation logs everything needed to track it down, then the baselines are reset so one slip isn't reported on every check after it
+
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checkpoint {
+    Trade,
+    Settlement,
+}
+
+impl MatchMaker {
+    pub fn held_cards(&self) -> Inventory {
+        let mut held = Inventory::new();
+        for inventory in self.player_inventories.values() {
+            held.spades += inventory.spades;
+            held.clubs += inventory.clubs;
+            held.diamonds += inventory.diamonds;
+            held.hearts += inventory.hearts;
+        }
+        held
+    }
+
+    pub fn held_points(&self) -> usize {
+        self.player_points.values().sum()
+    }
+
+    pub fn audit(&self, checkpoint: Checkpoint) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        // =-= Cards =-= //
+        let held = self.held_cards();
+        for card in SUITS.iter() {
+            if held.get(card) != self.dealt_cards.get(card) {
+                violations.push(format!("{} in hands: {}x, but {}x were dealt", card.plural(), held.get(card), self.dealt_cards.get(card)));
+            }
+        }
+
+        // =-= Points =-= //
+        let points = self.held_points();
+        if points + self.pot != self.bank {
+            violations.push(format!("points ({}) + pot ({}) = {}, but the table holds {}", points, self.pot, points + self.pot, self.bank));
+        }
+        if checkpoint == Checkpoint::Settlement && self.pot != 0 {
+            violations.push(format!("{} points left in the pot after settlement", self.pot));
+        }
+
+        // =-= Books =-= //
+        for card in SUITS.iter() {
+            let Some(book) = self.books.get(card) else {
+                violations.push(format!("no book for {}", card.plural()));
+                continue;
+            };
+            if book.ask.player_name != PlayerName::None {
+                match self.player_inventories.get(&book.ask.player_name) {
+                    Some(inventory) if inventory.get(card) > 0 => {},
+                    Some(_) => violations.push(format!("{:?} is asking {} for {} without holding any", book.ask.player_name, book.ask.price, card.plural())),
+                    None => violations.push(format!("{:?} is asking on {} but isn't seated", book.ask.player_name, card.plural())),
+                }
+            }
+            if book.bid.player_name != PlayerName::None {
+                match self.player_points.get(&book.bid.player_name) {
+                    Some(points) if *points >= book.bid.price => {},
+                    Some(points) => violations.push(format!("{:?} is bidding {} for {} with only {} points", book.bid.player_name, book.bid.price, card.plural(), points)),
+                    None => violations.push(format!("{:?} is bidding on {} but isn't seated", book.bid.player_name, card.plural())),
+                }
+            }
+        }
+
+        violations
+    }
+
+    pub fn run_audit(&mut self, checkpoint: Checkpoint) {
+        if !self.audit_mode {
+            return;
+        }
+        let violations = self.audit(checkpoint);
+        if violations.is_empty() {
+            return;
+        }
+        self.audit_violations += violations.len();
+
+        for violation in violations.iter() {
+            error!(round = self.round, checkpoint = ?checkpoint, violation = %violation, "audit failed");
+        }
+        narrate!(
+            error!(round = self.round, checkpoint = ?checkpoint, pot = self.pot, bank = self.bank, dealt = ?self.dealt_cards, "audit state"),
+            "{}   Pot: {} | Bank: {} | Dealt: {:?}{}", CL::Dull.get(), self.pot, self.bank, self.dealt_cards, CL::End.get()
+        );
+        for player_name in &self.player_names {
+            narrate!(
+                error!(round = self.round, player = %player_name, points = ?self.player_points.get(player_name), hand = ?self.player_inventories.get(player_name), "audit state"),
+                "{}   {:?} |:| Points: {:?} | {:?}{}", CL::Dull.get(), player_name, self.player_points.get(player_name), self.player_inventories.get(player_name), CL::End.get()
+            );
+        }
+        for card in SUITS.iter() {
+            if let Some(book) = self.books.get(card) {
+                narrate!(
+                    error!(round = self.round, suit = ?card, bid = book.bid.price, bidder = %book.bid.player_name, ask = book.ask.price, asker = %book.ask.player_name, "audit state"),
+                    "{}   {:?} |:| Bid: {:?} | Ask: {:?}{}", CL::Dull.get(), card, book.bid, book.ask, CL::End.get()
+                );
+            }
+        }
+        if let Some(trade) = self.round_trades.last() {
+            narrate!(
+                error!(round = self.round, suit = ?trade.card, price = trade.price, buyer = %trade.buyer, seller = %trade.seller, seq = trade.seq, "audit state, last trade"),
+                "{}   Last trade: {:?}{}", CL::Dull.get(), trade, CL::End.get()
+            );
+        }
+
+        self.dealt_cards = self.held_cards();
+        self.bank = self.held_points() + self.pot;
+    }
+}
@@ -0,0 +1,58 @@
+use super::{MatchMaker, Card, Cancel, Direction, Ack, AckStatus, PlayerName};
+use tracing::info;
+
+
+// =-= Coverage =-= //
+// - an order is only checked against the player's hand (or points) when it arrives, but a resting quote can outlive what's behind it
+// (e.g. quotes left over from last round meeting a fresh deal, or a bid from a player the ante has since left short)
+// - each resting ask commits one card of its suit and each resting bid commits its price in points,
+// so before anything can hit them we make sure the player still has both
+// - a quote that's no longer covered is pulled and its owner told, trading against it would have moved cards or points that aren't there
+
+impl MatchMaker {
+    pub fn covered(&self, player_name: &PlayerName, card: &Card) -> bool {
+        match self.player_inventories.get(player_name) {
+            Some(inventory) => inventory.get(card) > 0,
+            None => false,
+        }
+    }
+
+    pub fn afforded(&self, player_name: &PlayerName, price: usize) -> bool {
+        match self.player_points.get(player_name) {
+            Some(points) => *points >= price,
+            None => false,
+        }
+    }
+
+    pub fn pull_uncovered_quotes(&mut self) -> bool {
+        // returns whether any quote was pulled, so the caller can let everyone know the books changed
+        let timestamp = self.engine_start.elapsed().as_micros() as u64;
+        let mut uncovered: Vec<(Card, Direction, PlayerName, usize, u64)> = Vec::new();
+        for (card, book) in self.books.iter() {
+            if book.ask.player_name != PlayerName::None && !self.covered(&book.ask.player_name, card) {
+                uncovered.push((card.clone(), Direction::Sell, book.ask.player_name.clone(), book.ask.price, book.ask.seq));
+            }
+            if book.bid.player_name != PlayerName::None && !self.afforded(&book.bid.player_name, book.bid.price) {
+                uncovered.push((card.clone(), Direction::Buy, book.bid.player_name.clone(), book.bid.price, book.bid.seq));
+            }
+        }
+
+        for (card, direction, player_name, price, seq) in uncovered.iter() {
+            match direction {
+                Direction::Sell => info!(round = self.round, player = %player_name, suit = ?card, price, "pulled ask, no longer holds the card"),
+                Direction::Buy => info!(round = self.round, player = %player_name, suit = ?card, price, "pulled bid, can no longer pay for it"),
+            }
+            self.cancel_quote(&Cancel { player_name: player_name.clone(), direction: direction.clone(), card: card.clone() });
+            self.acknowledge(Ack {
+                player_name: player_name.clone(),
+                seq: *seq,
+                timestamp,
+                card: card.clone(),
+                direction: direction.clone(),
+                price: Some(*price),
+                status: AckStatus::Uncovered,
+            });
+        }
+        !uncovered.is_empty()
+    }
+}
@@ -0,0 +1,264 @@
+use super::{Request, AckStatus, Trade};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+
+// =-= Metrics =-= //
+// - counters, gauges and histograms of how the match maker itself is holding up, as opposed to how the players are doing
+// - rendered in the Prometheus text format (`GET /metrics` on the spectator server, see `spectator/mod.rs`)
+// and summarised after every round from the difference since the round started (see `since`)
+// - counters and histograms only ever go up, gauges are whatever they were last, the backlog high is reset every round
+// - labels are kept in BTreeMaps so the exposition comes out in the same order every time
+
+pub const EVENT_CAPACITY: usize = 128; // the event broadcast's, a power of two is what the channel rounds up to anyway
+
+const SECONDS: [f64; 12] = [0.000_01, 0.000_025, 0.000_05, 0.000_1, 0.000_25, 0.000_5, 0.001, 0.002_5, 0.005, 0.01, 0.05, 0.25];
+const WAITS: [f64; 10] = [0.000_1, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0, 5.0];
+const DEPTHS: [f64; 10] = [0.0, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0];
+
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub bounds: &'static [f64], // upper bounds, there's always a +Inf bucket past the last one
+    pub counts: Vec<u64>, // per bucket, not cumulative
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self { bounds, counts: vec![0; bounds.len() + 1], sum: 0.0, count: 0 }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.iter().position(|bound| value <= *bound).unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn quantile(&self, quantile: f64) -> Option<f64> {
+        // the upper bound of the bucket the quantile falls in, so it's an "at most", infinite past the last bound
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (quantile * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(self.bounds.get(bucket).copied().unwrap_or(f64::INFINITY));
+            }
+        }
+        Some(f64::INFINITY)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 { None } else { Some(self.sum / self.count as f64) }
+    }
+
+    pub fn since(&self, earlier: &Histogram) -> Self {
+        Self {
+            bounds: self.bounds,
+            counts: self.counts.iter().zip(earlier.counts.iter()).map(|(now, then)| now.saturating_sub(*then)).collect(),
+            sum: self.sum - earlier.sum,
+            count: self.count.saturating_sub(earlier.count),
+        }
+    }
+
+    pub fn render(&self, output: &mut String, name: &str, help: &str) {
+        output.push_str(&format!("# HELP {} {}\n# TYPE {} histogram\n", name, help, name));
+        let mut cumulative = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            let bound = self.bounds.get(bucket).map(|bound| bound.to_string()).unwrap_or(String::from("+Inf"));
+            output.push_str(&format!("{}_bucket{{le=\"{}\"}} {}\n", name, bound, cumulative));
+        }
+        output.push_str(&format!("{}_sum {}\n{}_count {}\n", name, self.sum, name, self.count));
+    }
+}
+
+fn label<T: Serialize>(value: &T) -> String {
+    // the same names the gateway protocol uses, e.g. "rate_limited"
+    serde_json::to_value(value).ok().and_then(|value| value.as_str().map(String::from)).unwrap_or_default()
+}
+
+fn since(now: &BTreeMap<String, u64>, earlier: &BTreeMap<String, u64>) -> BTreeMap<String, u64> {
+    now.iter().map(|(key, count)| (key.clone(), count.saturating_sub(earlier.get(key).copied().unwrap_or(0)))).collect()
+}
+
+fn render_counter(output: &mut String, name: &str, help: &str, label_name: &str, counts: &BTreeMap<String, u64>) {
+    output.push_str(&format!("# HELP {} {}\n# TYPE {} counter\n", name, help, name));
+    for (key, count) in counts.iter() {
+        output.push_str(&format!("{}{{{}=\"{}\"}} {}\n", name, label_name, key, count));
+    }
+}
+
+fn render_single(output: &mut String, name: &str, help: &str, kind: &str, value: impl std::fmt::Display) {
+    output.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n{} {}\n", name, help, name, kind, name, value));
+}
+
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    pub received: BTreeMap<String, u64>, // requests taken off the order channel, by kind
+    pub acks: BTreeMap<String, u64>, // by status, every rejection counts as "rejected"
+    pub rejected: BTreeMap<String, u64>, // and by reason
+    pub trades: BTreeMap<String, u64>, // by suit
+    pub dropped: u64, // requests still queued when a trade reset the books
+    pub overruns: u64, // events sent with the broadcast already full, each one left a receiver lagging
+    pub queue: usize, // the order channel (`order_receiver.len()`), as of the last request processed
+    pub throttle_queue: usize, // requests held by the throttle
+    pub backlog: usize, // events the slowest receiver has yet to read
+    pub backlog_high: usize, // the most it got to this round
+    pub receivers: usize,
+    pub queue_depth: Histogram, // channel and throttle together, every time a request comes off
+    pub processing: Histogram, // seconds spent in `process`, per request
+    pub wait: Histogram, // seconds from being stamped to being processed
+    pub drained: Histogram, // requests dropped per trade
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            received: BTreeMap::new(),
+            acks: BTreeMap::new(),
+            rejected: BTreeMap::new(),
+            trades: BTreeMap::new(),
+            dropped: 0,
+            overruns: 0,
+            queue: 0,
+            throttle_queue: 0,
+            backlog: 0,
+            backlog_high: 0,
+            receivers: 0,
+            queue_depth: Histogram::new(&DEPTHS),
+            processing: Histogram::new(&SECONDS),
+            wait: Histogram::new(&WAITS),
+            drained: Histogram::new(&DEPTHS),
+        }
+    }
+
+    pub fn record_request(&mut self, request: &Request) {
+        let kind = match request {
+            Request::Order(_) => "order",
+            Request::Cancel(_) => "cancel",
+            Request::Disconnect(_) => "disconnect",
+        };
+        *self.received.entry(kind.to_string()).or_default() += 1;
+    }
+
+    pub fn record_ack(&mut self, status: &AckStatus) {
+        let status = match status {
+            AckStatus::Rejected(reason) => {
+                *self.rejected.entry(label(reason)).or_default() += 1;
+                String::from("rejected")
+            },
+            status => label(status),
+        };
+        *self.acks.entry(status).or_default() += 1;
+    }
+
+    pub fn record_queue(&mut self, queue: usize, throttle_queue: usize) {
+        self.queue = queue;
+        self.throttle_queue = throttle_queue;
+        self.queue_depth.observe((queue + throttle_queue) as f64);
+    }
+
+    pub fn record_processing(&mut self, processing: Duration, wait: Duration) {
+        self.processing.observe(processing.as_secs_f64());
+        self.wait.observe(wait.as_secs_f64());
+    }
+
+    pub fn record_trade(&mut self, trade: &Trade, drained: usize) {
+        *self.trades.entry(format!("{:?}", trade.card)).or_default() += 1;
+        self.dropped += drained as u64;
+        self.drained.observe(drained as f64);
+    }
+
+    pub fn record_broadcast(&mut self, backlog: usize, receivers: usize) {
+        // called just before a send, a full buffer means the slowest receiver is about to lose its oldest event
+        if backlog >= EVENT_CAPACITY {
+            self.overruns += 1;
+        }
+        self.backlog = backlog;
+        self.backlog_high = self.backlog_high.max(backlog);
+        self.receivers = receivers;
+    }
+
+    pub fn reset_round(&mut self) {
+        self.backlog_high = self.backlog;
+    }
+
+    pub fn since(&self, earlier: &Metrics) -> Self {
+        // counters and histograms since `earlier`, gauges as they are now
+        Self {
+            received: since(&self.received, &earlier.received),
+            acks: since(&self.acks, &earlier.acks),
+            rejected: since(&self.rejected, &earlier.rejected),
+            trades: since(&self.trades, &earlier.trades),
+            dropped: self.dropped.saturating_sub(earlier.dropped),
+            overruns: self.overruns.saturating_sub(earlier.overruns),
+            queue_depth: self.queue_depth.since(&earlier.queue_depth),
+            processing: self.processing.since(&earlier.processing),
+            wait: self.wait.since(&earlier.wait),
+            drained: self.drained.since(&earlier.drained),
+            ..self.clone()
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        render_counter(&mut output, "figgie_requests_received_total", "Requests taken off the order channel.", "kind", &self.received);
+        render_counter(&mut output, "figgie_acks_total", "Acks sent, by status.", "status", &self.acks);
+        render_counter(&mut output, "figgie_requests_rejected_total", "Requests rejected, by reason.", "reason", &self.rejected);
+        render_counter(&mut output, "figgie_trades_total", "Trades, by suit.", "suit", &self.trades);
+        render_single(&mut output, "figgie_requests_dropped_total", "Requests still queued when a trade reset the books.", "counter", self.dropped);
+        render_single(&mut output, "figgie_event_overruns_total", "Events sent with the broadcast full, each left a receiver lagging.", "counter", self.overruns);
+        render_single(&mut output, "figgie_order_queue", "Requests waiting on the order channel.", "gauge", self.queue);
+        render_single(&mut output, "figgie_throttle_queue", "Requests held back by the throttle.", "gauge", self.throttle_queue);
+        render_single(&mut output, "figgie_event_backlog", "Events the slowest receiver has yet to read.", "gauge", self.backlog);
+        render_single(&mut output, "figgie_event_backlog_high", "The most the event backlog got to this round.", "gauge", self.backlog_high);
+        render_single(&mut output, "figgie_event_receivers", "Receivers subscribed to the event broadcast.", "gauge", self.receivers);
+        self.queue_depth.render(&mut output, "figgie_queue_depth", "Requests queued when one comes off.");
+        self.processing.render(&mut output, "figgie_processing_seconds", "Time spent processing a request.");
+        self.wait.render(&mut output, "figgie_wait_seconds", "Time from a request being stamped to being processed.");
+        self.drained.render(&mut output, "figgie_drained_per_trade", "Requests dropped by each trade.");
+        output
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        // a few lines for the end of a round, e.g. " - Processing: p50 <= 25µs | p99 <= 250µs"
+        let counts = |counts: &BTreeMap<String, u64>| -> String {
+            let counts: Vec<String> = counts.iter().filter(|(_, count)| **count > 0).map(|(key, count)| format!("{} {}", key, count)).collect();
+            if counts.is_empty() { String::from("-") } else { counts.join(" | ") }
+        };
+        let micros = |seconds: Option<f64>| match seconds {
+            Some(seconds) if seconds.is_finite() => format!("<= {}µs", (seconds * 1e6).round()),
+            Some(_) => String::from("over the top bucket"),
+            None => String::from("-"),
+        };
+        let depth = |depth: Option<f64>| match depth {
+            Some(depth) if depth.is_finite() => format!("<= {}", depth),
+            Some(_) => format!("> {}", DEPTHS[DEPTHS.len() - 1]),
+            None => String::from("-"),
+        };
+
+        vec![
+            format!(" - Requests: {}x |:| {}", self.received.values().sum::<u64>(), counts(&self.received)),
+            format!(" - Acks: {}", counts(&self.acks)),
+            format!(" - Rejected: {}", counts(&self.rejected)),
+            format!(" - Dropped after trades: {}x |:| Per trade: mean {:.1}", self.dropped, self.drained.mean().unwrap_or(0.0)),
+            format!(" - Queue depth: p50 {} | p99 {}", depth(self.queue_depth.quantile(0.5)), depth(self.queue_depth.quantile(0.99))),
+            format!(" - Processing: p50 {} | p99 {}", micros(self.processing.quantile(0.5)), micros(self.processing.quantile(0.99))),
+            format!(" - Wait: p50 {} | p99 {}", micros(self.wait.quantile(0.5)), micros(self.wait.quantile(0.99))),
+            format!(" - Event backlog: high {}/{} | overruns {}x | receivers {}", self.backlog_high, EVENT_CAPACITY, self.overruns, self.receivers),
+            format!(" - Trades: {}", counts(&self.trades)),
+        ]
+    }
+}
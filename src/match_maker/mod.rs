@@ -1,5 +1,8 @@
-use super::{Card, Book, Inventory, Order, Event, Update, Trade, Direction, CL, PlayerName};
+use super::{Card, Book, Inventory, Request, Order, Cancel, Stamp, Sequenced, Ack, AckStatus, RejectReason, EngineError, Event, Update, Trade, Direction, Snapshot, SNAPSHOT_TAPE, CL, PlayerName};
 use tokio::sync::broadcast::Sender;
+use tokio::sync::broadcast::error::SendError;
+use tokio::sync::watch;
+use tokio::time::{timeout, Duration, Instant};
 use rand::prelude::SliceRandom;
 use kanal::AsyncReceiver;
 use rand::rngs::StdRng;
@@ -7,6 +10,22 @@ use rand::SeedableRng;
 use std::sync::Arc;
 use rand::Rng;
 use std::collections::HashMap;
+use super::analytics::{Analytics, Payout, RoundReport, Tables, GOAL_CARD_VALUE};
+use tracing::{debug, info, warn, error};
+
+pub mod throttle;
+pub use throttle::*;
+
+pub mod self_trade;
+pub use self_trade::SelfTradePrevention;
+
+pub mod coverage;
+
+pub mod audit;
+pub use audit::Checkpoint;
+
+pub mod metrics;
+pub use metrics::*;
 
 pub struct MatchMaker {
     pub round: u32,
@@ -18,8 +37,34 @@ pub struct MatchMaker {
     pub books: HashMap<Card, Book>,
     pub player_inventories: HashMap<PlayerName, Inventory>,
     pub event_sender: Sender<Event>,
-    pub order_receiver: Arc<AsyncReceiver<Order>>,
+    pub order_receiver: Arc<AsyncReceiver<Request>>,
     pub rng: StdRng,
+    pub round_duration: Duration,
+    pub rounds: Option<u32>, // stop after this many, or play forever
+    pub round_start: Option<Instant>,
+    pub trading: bool,
+    pub round_trades: Vec<Trade>,
+    pub round_orders: usize,
+    pub point_changes: HashMap<PlayerName, i64>,
+    pub snapshot_sender: watch::Sender<Snapshot>,
+    pub throttle: Throttle,
+    pub engine_start: Instant,
+    pub next_seq: u64,
+    pub batch_window: Option<Duration>, // see `batch_ready`
+    pub batch_rng: StdRng, // kept apart from the dealing rng, so batching doesn't change the deals
+    pub batch_deadline: Option<Instant>,
+    pub batch_remaining: usize,
+    pub self_trade_prevention: SelfTradePrevention,
+    pub pot: usize,
+    pub bank: usize, // every point at the table, pot included
+    pub dealt_cards: Inventory,
+    pub audit_mode: bool, // see `audit.rs`
+    pub audit_violations: usize,
+    pub antes: HashMap<PlayerName, usize>, // what each seat paid in this round
+    pub payouts: HashMap<PlayerName, Payout>, // and what it was paid out at settlement
+    pub analytics: Analytics,
+    pub tables: Tables, // see `analytics/tables.rs`
+    pub metrics: Metrics, // see `metrics.rs`
 }
 
 impl MatchMaker {
@@ -27,9 +72,10 @@ impl MatchMaker {
         starting_balance: usize,
         player_names: Vec<PlayerName>,
         event_sender: Sender<Event>,
-        order_receiver: Arc<AsyncReceiver<Order>>,
+        order_receiver: Arc<AsyncReceiver<Request>>,
     ) -> Self {
 
+        let bank = starting_balance * player_names.len();
         let mut player_inventories = HashMap::new();
         let mut player_points = HashMap::new();
         for player_name in &player_names {
@@ -56,9 +102,41 @@ impl MatchMaker {
             event_sender,
             order_receiver,
             rng: StdRng::from_entropy(),
+            round_duration: Duration::from_secs(60 * 4), // 4 minutes per round
+            rounds: None,
+            round_start: None,
+            trading: false,
+            round_trades: Vec::new(),
+            round_orders: 0,
+            point_changes: HashMap::new(),
+            snapshot_sender: watch::Sender::new(Snapshot::new()),
+            throttle: Throttle::new(ThrottleConfig::default()),
+            engine_start: Instant::now(),
+            next_seq: 1,
+            batch_window: None,
+            batch_rng: StdRng::from_entropy(),
+            batch_deadline: None,
+            batch_remaining: 0,
+            self_trade_prevention: SelfTradePrevention::Reject,
+            pot: 0,
+            bank,
+            dealt_cards: Inventory::new(),
+            audit_mode: false,
+            audit_violations: 0,
+            antes: HashMap::new(),
+            payouts: HashMap::new(),
+            analytics: Analytics::default(),
+            tables: Tables::default(),
+            metrics: Metrics::new(),
         }
     }
 
+    pub fn seed(&mut self, seed: u64) {
+        // the same seed deals the same hands and shuffles batches the same way, the order flow is up to the players
+        self.rng = StdRng::seed_from_u64(seed);
+        self.batch_rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+    }
+
     pub fn pick_new_common_suit(&mut self) {
         self.common_suit = self.suits[self.rng.gen_range(0..=3)].clone();
     }
@@ -72,27 +150,27 @@ impl MatchMaker {
         
         let mut starting_inventory = HashMap::new();
 
-        println!("=---= Card Count =---=");
-        println!("{} - {:?} | 12x{}", CL::Dull.get(), self.common_suit, CL::End.get());
+        console!("=---= Card Count =---=");
+        console!("{} - {:?} | 12x{}", CL::Dull.get(), self.common_suit, CL::End.get());
         starting_inventory.insert(self.common_suit.clone(), 12);
 
         // randomly pick one of the other 3 suits to be the one with 8 cards
         let mut already_lucky = false;
         for (idx, suit) in [suit_1, suit_2, goal_suit].iter().enumerate() {
-            let lucky_eight = rand::random::<bool>();
+            let lucky_eight = self.rng.gen::<bool>();
             if idx == 2 && !already_lucky {
                 for _ in 0..8 { cards.push(suit.clone()) }
-                println!("{} - {:?} | 8x{}", CL::Dull.get(), suit, CL::End.get());
+                console!("{} - {:?} | 8x{}", CL::Dull.get(), suit, CL::End.get());
                 starting_inventory.insert(suit.clone(), 8);
             } else {
                 if !already_lucky && lucky_eight {
                     for _ in 0..8 { cards.push(suit.clone()) }
-                    println!("{} - {:?} | 8x{}", CL::Dull.get(), suit, CL::End.get());
+                    console!("{} - {:?} | 8x{}", CL::Dull.get(), suit, CL::End.get());
                     starting_inventory.insert(suit.clone(), 8);
                     already_lucky = true;
                 } else {
                     for _ in 0..10 { cards.push(suit.clone()) }
-                    println!("{} - {:?} | 10x{}", CL::Dull.get(), suit, CL::End.get());
+                    console!("{} - {:?} | 10x{}", CL::Dull.get(), suit, CL::End.get());
                     starting_inventory.insert(suit.clone(), 10);
                 }
             }
@@ -106,7 +184,7 @@ impl MatchMaker {
         for (i, player_name) in self.player_names.iter().enumerate() {
             let mut player_inventory = Inventory::new();
             player_inventory.count(chunks[i].to_vec());
-            self.player_inventories.insert(player_name.clone(), player_inventory.clone());
+            self.player_inventories.insert(player_name.clone(), player_inventory);
         }
 
         starting_inventory
@@ -114,312 +192,600 @@ impl MatchMaker {
 
 
 
-    pub async fn start(&mut self) {
-        let round_duration = tokio::time::Duration::from_secs(60 * 4); // 4 minutes per round
+    pub fn cancel_quote(&mut self, cancel: &Cancel) -> bool {
+        // players can only pull their own quote, returns whether anything changed
+        let Some(book) = self.books.get_mut(&cancel.card) else { return false };
+        let quote = match cancel.direction {
+            Direction::Buy => &mut book.bid,
+            Direction::Sell => &mut book.ask,
+        };
+        if quote.player_name != cancel.player_name {
+            return false;
+        }
 
-        loop {
-            let mut pot = 0;
-            let ante = 200 / self.player_names.len();
+        info!(round = self.round, player = %cancel.player_name, suit = ?cancel.card, direction = ?cancel.direction, price = quote.price, "cancelled");
+        quote.price = match cancel.direction {
+            Direction::Buy => 0,
+            Direction::Sell => 99,
+        };
+        quote.player_name = PlayerName::None;
+        quote.seq = 0;
+        true
+    }
 
-            println!("{}==================== ROUND {} ===================={}", CL::Purple.get(), self.round, CL::End.get());
-            println!("");
-            println!("=---= Game Details =---=");
-            println!("{} - Players: {}x{}", CL::Dull.get(), self.player_names.len(), CL::End.get());
-            println!("{} - Ante: {}{}", CL::Dull.get(), ante, CL::End.get());
-            println!("{} - Pot: 200{}", CL::Dull.get(), CL::End.get());
-            println!("");
-            
-            let initial_points = self.player_points.clone();
-            for (player, points) in self.player_points.iter_mut() {
-                if *points < ante {
-                    println!("[!] Player {:?} does not have enough points to play", player);
-                    break;
-                }
-                *points -= ante;
-                pot += ante;
+    pub fn disconnect(&mut self, player_name: &PlayerName) {
+        // nobody is left to manage the seat's quotes, so they're pulled, and whatever it still had waiting is dropped
+        info!(round = self.round, player = %player_name, "disconnected");
+        let mut pulled = false;
+        for card in [Card::Spade, Card::Club, Card::Diamond, Card::Heart] {
+            for direction in [Direction::Buy, Direction::Sell] {
+                pulled |= self.cancel_quote(&Cancel { player_name: player_name.clone(), direction, card: card.clone() });
             }
+        }
+        for sequenced in self.throttle.remove(player_name) {
+            self.acknowledge(Ack::new(&sequenced, AckStatus::Dropped));
+        }
+        if pulled {
+            self.send_update(None);
+        }
+    }
 
-            self.pick_new_common_suit();
-            let starting_inventory = self.get_new_inventories();
+    pub fn book(&self, card: &Card) -> Book {
+        self.books.get(card).cloned().unwrap_or_default()
+    }
 
-            println!("{} - Common suit: {:?}{}", CL::Dull.get(), self.common_suit, CL::End.get());
-            println!("{} - Goal suit: {}{:?}{}{}", CL::Dull.get(), CL::LimeGreen.get(), self.goal_suit, CL::End.get(), CL::End.get());
-            println!("");
+    pub fn validate_order(&self, order: &Order) -> Result<(), EngineError> {
+        let inventory = self.player_inventories.get(&order.player_name).ok_or(EngineError::UnknownPlayer(order.player_name.clone()))?;
+        let points = self.player_points.get(&order.player_name).copied().ok_or(EngineError::UnknownPlayer(order.player_name.clone()))?;
+        match order.direction {
+            Direction::Sell if inventory.get(&order.card) == 0 => Err(EngineError::InsufficientInventory(order.card.clone())),
+            Direction::Buy if points < order.price => Err(EngineError::InsufficientPoints { player_name: order.player_name.clone(), needed: order.price, available: points }),
+            _ => Ok(()),
+        }
+    }
 
-            println!("{}[+] Dealing cards...{}\n", CL::DimLightBlue.get(), CL::End.get());
-            
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await; // give the players a little bit to get ready
-            
-            if let Err(e) = self.event_sender.send(Event::DealCards(self.player_inventories.clone())) {
-                println!("{}[!] Error sending deal cards event: {:?}{}", CL::Red.get(), e, CL::End.get());
-            }
+    pub fn settle_trade(&mut self, card: &Card, buyer: &PlayerName, seller: &PlayerName, price: usize) -> Result<(), EngineError> {
+        // everything is worked out on copies first, so either the whole trade lands or none of it does
+        let mut buyer_inventory = *self.player_inventories.get(buyer).ok_or(EngineError::UnknownPlayer(buyer.clone()))?;
+        let mut seller_inventory = *self.player_inventories.get(seller).ok_or(EngineError::UnknownPlayer(seller.clone()))?;
+        let buyer_points = self.player_points.get(buyer).copied().ok_or(EngineError::UnknownPlayer(buyer.clone()))?;
+        let seller_points = self.player_points.get(seller).copied().ok_or(EngineError::UnknownPlayer(seller.clone()))?;
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await; // give the players some time to order their cards
+        seller_inventory.change(card.clone(), false)?;
+        let buyer_points = buyer_points.checked_sub(price).ok_or(EngineError::InsufficientPoints { player_name: buyer.clone(), needed: price, available: buyer_points })?;
 
-            // send out the book
-            let book_event = Event::Update(Update {
-                spades: self.books.get(&Card::Spade).unwrap().clone(),
-                clubs: self.books.get(&Card::Club).unwrap().clone(),
-                diamonds: self.books.get(&Card::Diamond).unwrap().clone(),
-                hearts: self.books.get(&Card::Heart).unwrap().clone(),
-                trade: None,
-            });
-            if let Err(e) = self.event_sender.send(book_event) {
-                println!("[!] Error sending book event: {:?}", e);
+        if buyer == seller {
+            return Ok(()); // a self-trade (only with self-trade prevention off) nets out to nothing
+        }
+        buyer_inventory.change(card.clone(), true)?;
+        let seller_points = seller_points.checked_add(price).ok_or(EngineError::Overflow)?;
+
+        self.player_inventories.insert(buyer.clone(), buyer_inventory);
+        self.player_inventories.insert(seller.clone(), seller_inventory);
+        self.player_points.insert(buyer.clone(), buyer_points);
+        self.player_points.insert(seller.clone(), seller_points);
+        Ok(())
+    }
+
+    pub fn match_order(&mut self, stamp: Stamp, order: &Order) -> Result<(Option<Trade>, AckStatus), EngineError> {
+        let book = self.books.get(&order.card).ok_or(EngineError::UnknownBook(order.card.clone()))?;
+        let (resting, crosses) = match order.direction {
+            Direction::Buy => (book.ask.clone(), order.price >= book.ask.price),
+            Direction::Sell => (book.bid.clone(), order.price <= book.bid.price),
+        };
+
+        // an empty side of the book has nobody to trade with, so the order can only rest
+        if crosses && resting.player_name != PlayerName::None {
+            info!(round = self.round, player = %order.player_name, resting = %resting.player_name, suit = ?order.card, direction = ?order.direction, price = resting.price, "matched");
+            let (buyer, seller) = match order.direction {
+                Direction::Buy => (order.player_name.clone(), resting.player_name.clone()),
+                Direction::Sell => (resting.player_name.clone(), order.player_name.clone()),
+            };
+
+            // =-= Update the Inventories & Points =-= //
+            self.settle_trade(&order.card, &buyer, &seller, resting.price)?;
+
+            // =-= Package Trade =-= //
+            if let Some(book) = self.books.get_mut(&order.card) {
+                book.update_last_trade(resting.price);
             }
+            let trade = Trade {
+                card: order.card.clone(),
+                price: resting.price,
+                buyer,
+                seller,
+                seq: stamp.seq,
+                resting_seq: resting.seq,
+                aggressor: order.direction.clone(),
+                timestamp: stamp.timestamp,
+            };
+            return Ok((Some(trade), AckStatus::Filled));
+        }
 
-            let (spades_color, clubs_color, diamonds_color, hearts_color) = self.goal_suit.get_book_colors();
+        // check if this price beats the current best quote on its side
+        let book = self.books.get_mut(&order.card).ok_or(EngineError::UnknownBook(order.card.clone()))?;
+        let (improves, quote) = match order.direction {
+            Direction::Buy => (order.price > book.bid.price, &mut book.bid),
+            Direction::Sell => (order.price < book.ask.price, &mut book.ask),
+        };
+        if !improves {
+            return Ok((None, AckStatus::NotImproved));
+        }
+        quote.price = order.price;
+        quote.player_name = order.player_name.clone();
+        quote.seq = stamp.seq;
+        Ok((None, AckStatus::Resting))
+    }
 
-            let start = tokio::time::Instant::now();
-            while start.elapsed() < round_duration {
+    pub fn stamp(&mut self, request: Request) -> Sequenced {
+        let stamp = Stamp { seq: self.next_seq, timestamp: self.engine_start.elapsed().as_micros() as u64 };
+        self.next_seq += 1;
+        Sequenced { stamp, request }
+    }
 
-                if let Ok(order) = self.order_receiver.recv().await {
-                    if order.price == 0 { // No free lunches allowed
-                        continue;
-                    }
+    pub fn broadcast(&mut self, event: Event) -> Result<usize, SendError<Event>> {
+        // every event goes out through here, so the metrics see how far behind the slowest receiver is
+        self.metrics.record_broadcast(self.event_sender.len(), self.event_sender.receiver_count());
+        self.event_sender.send(event)
+    }
 
-                    println!("Processing order: {:?} | Queue: {}x", order, self.order_receiver.len());
-
-                    let book = self.books.get_mut(&order.card).unwrap();
-                    let trade: Option<Trade> = match order.direction {
-                        Direction::Buy => {
-                            if order.price >= book.ask.price {
-                                println!("{}[-] Aggressing Player: {:?} | {:?} |:| Matched buy order!{}", CL::Green.get(), order.player_name, order.card, CL::End.get());
-
-
-                                // =-= Update the Inventories =-= //
-                                let buyer_inventory = self.player_inventories.get_mut(&order.player_name).unwrap();
-                                buyer_inventory.change(order.card.clone(), true);
-
-                                let seller_inventory = self.player_inventories.get_mut(&book.ask.player_name).unwrap();
-                                seller_inventory.change(order.card.clone(), false);
-
-
-                                // =-= Update the Points =-= //
-                                let buyer_points = self.player_points.get_mut(&order.player_name).unwrap();
-                                *buyer_points -= book.ask.price;
-
-                                let seller_points = self.player_points.get_mut(&book.ask.player_name).unwrap();
-                                *seller_points += book.ask.price;
-
-
-                                // =-= Package Trade =-= //
-                                book.last_trade = Some(book.ask.price);
-                                let trade = Trade {
-                                    card: order.card.clone(),
-                                    price: book.ask.price,
-                                    buyer: order.player_name,
-                                    seller: book.ask.player_name.clone(),
-                                };
-                                Some(trade)
-
-                            } else {
-                                // check if this price beats the current best bid
-                                if order.price > book.bid.price {
-                                    // update the bid price and user_id
-                                    book.bid.price = order.price;
-                                    book.bid.player_name = order.player_name;
-                                }
-                                None
-                            }
-                        },
-                        Direction::Sell => {
-                            // check if the user has the inventory to sell this Card
-                            let seller_inventory = self.player_inventories.get(&order.player_name).unwrap();
-                            if seller_inventory.get(&order.card) == 0 {
-                                println!("[!] {:?} | {:?} |:| Player does not have the inventory to sell this Card", order.player_name, order.card);
-                                continue;
-                            }
-
-                            if order.price <= book.bid.price {
-                                println!("{}[-] Aggressing Player: {:?} | {:?} |:| Matched sell order!{}", CL::Red.get(), order.player_name, order.card, CL::End.get());
-
-                                // =-= Update the Inventories =-= //
-                                let buyer_inventory = self.player_inventories.get_mut(&book.bid.player_name).unwrap();
-                                buyer_inventory.change(order.card.clone(), true);
-
-                                let seller_inventory = self.player_inventories.get_mut(&order.player_name).unwrap();
-                                seller_inventory.change(order.card.clone(), false);
-
-
-                                // =-= Update the Points =-= //
-                                let buyer_points = self.player_points.get_mut(&book.bid.player_name).unwrap();
-                                *buyer_points -= book.bid.price;
-
-                                let seller_points = self.player_points.get_mut(&order.player_name).unwrap();
-                                *seller_points += book.bid.price;
-
-
-                                // =-= Package Trade =-= //
-                                book.last_trade = Some(book.bid.price);
-                                let trade = Trade {
-                                    card: order.card.clone(),
-                                    price: book.bid.price,
-                                    buyer: book.bid.player_name.clone(),
-                                    seller: order.player_name,
-                                };
-                                Some(trade)
-
-                            } else {
-                                // check if this price beats the current best bid
-                                if order.price < book.ask.price {
-                                    // update the bid price and user_id
-                                    book.ask.price = order.price;
-                                    book.ask.player_name = order.player_name;
-                                }
-                                None
-                            }
-                        },
-                    };
-
-                    if let Some(_) = trade.clone() {
-                        // =-= Reset all the Books =-= //
-                        self.books.get_mut(&Card::Spade).unwrap().reset_quotes();
-                        self.books.get_mut(&Card::Club).unwrap().reset_quotes();
-                        self.books.get_mut(&Card::Diamond).unwrap().reset_quotes();
-                        self.books.get_mut(&Card::Heart).unwrap().reset_quotes();
-
-                        // =-= Drain the Order Receiver =-= //
-                        let drain_amount = self.order_receiver.len();
-                        for _ in 0..drain_amount {
-                            let _ = self.order_receiver.try_recv();
-                        }
-                    }
+    pub fn acknowledge(&mut self, ack: Ack) {
+        if let AckStatus::Rejected(reason) = ack.status {
+            info!(round = self.round, player = %ack.player_name, seq = ack.seq, suit = ?ack.card, direction = ?ack.direction, price = ack.price, reason = ?reason, "rejected");
+        }
+        self.metrics.record_ack(&ack.status);
+        if let Err(e) = self.broadcast(Event::Ack(ack)) {
+            error!(round = self.round, error = %e, "unable to send ack event");
+        }
+    }
 
-                    // =-= Print the Game =-= //
-                    println!("\n=---------------------------------------------------------------------------------=");
-
-                    let spades = self.books.get(&Card::Spade).unwrap();
-                    let clubs = self.books.get(&Card::Club).unwrap();
-                    let diamonds = self.books.get(&Card::Diamond).unwrap();
-                    let hearts = self.books.get(&Card::Heart).unwrap();
-                    println!("{}Spades    {}|:| Bid: ({}{:?}{}, {:?}) | Ask: ({}{:?}{}, {:?}) |:|{} Last trade: {}{:?}{}", spades_color.get(), CL::Dull.get(), CL::Green.get(), spades.bid.price,    CL::Dull.get(), spades.bid.player_name,    CL::PeachRed.get(),  spades.ask.price,    CL::Dull.get(),  spades.ask.player_name,    CL::Dull.get(),  CL::DimLightBlue.get(),  spades.last_trade.unwrap_or_default(),    CL::End.get());
-                    println!("{}Clubs     {}|:| Bid: ({}{:?}{}, {:?}) | Ask: ({}{:?}{}, {:?}) |:|{} Last trade: {}{:?}{}", clubs_color.get(), CL::Dull.get(), CL::Green.get(), clubs.bid.price,     CL::Dull.get(), clubs.bid.player_name,     CL::PeachRed.get(),  clubs.ask.price,     CL::Dull.get(),  clubs.ask.player_name,     CL::Dull.get(),  CL::DimLightBlue.get(),  clubs.last_trade.unwrap_or_default(),     CL::End.get());
-                    println!("{}Diamonds  {}|:| Bid: ({}{:?}{}, {:?}) | Ask: ({}{:?}{}, {:?}) |:|{} Last trade: {}{:?}{}", diamonds_color.get(), CL::Dull.get(), CL::Green.get(), diamonds.bid.price,  CL::Dull.get(), diamonds.bid.player_name,  CL::PeachRed.get(),  diamonds.ask.price,  CL::Dull.get(),  diamonds.ask.player_name,  CL::Dull.get(),  CL::DimLightBlue.get(),  diamonds.last_trade.unwrap_or_default(),  CL::End.get());
-                    println!("{}Hearts    {}|:| Bid: ({}{:?}{}, {:?}) | Ask: ({}{:?}{}, {:?}) |:|{} Last trade: {}{:?}{}", hearts_color.get(), CL::Dull.get(), CL::Green.get(), hearts.bid.price,    CL::Dull.get(), hearts.bid.player_name,    CL::PeachRed.get(),  hearts.ask.price,    CL::Dull.get(),  hearts.ask.player_name,    CL::Dull.get(),  CL::DimLightBlue.get(),  hearts.last_trade.unwrap_or_default(),    CL::End.get());
-                    
-                    let mut inventory_string = format!("{}Points    {}|:|{} ", CL::DullGreen.get(), CL::Dull.get(), CL::DullGreen.get());
-                    for player_name in &self.player_names {
-                        let player_points = self.player_points.get(player_name).unwrap();
-                        inventory_string += &format!("{:?}: {} | ", player_name, player_points);
-                    }
-                    inventory_string.truncate(inventory_string.len() - 3);
+    pub fn admit(&mut self, request: Request) {
+        // every request is stamped the moment we take it off the queue, whatever happens to it after
+        // - except a disconnect, which is applied right away: it only takes risk off, and shouldn't wait behind the throttle
+        self.metrics.record_request(&request);
+        if let Request::Disconnect(player_name) = request {
+            self.disconnect(&player_name);
+            return;
+        }
+        let sequenced = self.stamp(request);
+        if let Err(ack) = self.throttle.enqueue(sequenced) {
+            self.acknowledge(ack);
+        }
+    }
+
+    pub fn batch_ready(&mut self) -> bool {
+        // - without a batch window requests go first come first served
+        // - with one, the first request to arrive opens the window and everything that makes it in before it closes
+        // is shuffled, so being a little faster than everyone else is worth nothing
+        let Some(window) = self.batch_window else { return true };
+        if self.batch_remaining > 0 || self.throttle.queue.is_empty() {
+            return true;
+        }
 
-                    println!("{}{}", inventory_string, CL::End.get());
-                    println!("=---------------------------------------------------------------------------------=\n");
+        let now = Instant::now();
+        let deadline = *self.batch_deadline.get_or_insert(now + window);
+        if now < deadline {
+            return false;
+        }
 
-                    let update = Update {
-                        spades: self.books.get(&Card::Spade).unwrap().clone(),
-                        clubs: self.books.get(&Card::Club).unwrap().clone(),
-                        diamonds: self.books.get(&Card::Diamond).unwrap().clone(),
-                        hearts: self.books.get(&Card::Heart).unwrap().clone(),
-                        trade,
-                    };
-                    let update_event = Event::Update(update);
+        self.throttle.queue.make_contiguous().shuffle(&mut self.batch_rng);
+        self.batch_remaining = self.throttle.queue.len();
+        self.batch_deadline = None;
+        true
+    }
 
-                    //println!("{}[+] Done processing request{}", CL::Green.get(), CL::End.get());
+    pub async fn next_request(&mut self, start: Instant) -> Option<Sequenced> {
+        // pull whatever is waiting into the throttle's queue, the outstanding limit is checked on the way in
+        while let Ok(Some(request)) = self.order_receiver.try_recv() {
+            self.admit(request);
+        }
 
-                    if let Err(e) = self.event_sender.send(update_event) {
-                        println!("[!] Error sending update event: {:?}", e);
-                    }
+        if self.batch_ready() {
+            if let Some(next) = self.throttle.next(Instant::now()) {
+                self.batch_remaining = self.batch_remaining.saturating_sub(1);
+                match next {
+                    Ok(sequenced) => return Some(sequenced),
+                    Err(ack) => {
+                        self.acknowledge(ack);
+                        return None;
+                    },
                 }
-            } 
+            }
+        }
 
-            // =-= End the Round =-= //
-            let end_round = Event::EndRound;
-            if let Err(e) = self.event_sender.send(end_round) {
-                println!("[!] Error sending end round event: {:?}", e);
+        // nothing can go yet, wait for a new request, a held back order's token, the batch to close or the end of the round
+        let mut wait = self.round_duration.saturating_sub(start.elapsed());
+        if let Some(ready) = self.throttle.next_ready() {
+            wait = wait.min(ready);
+        }
+        if let Some(deadline) = self.batch_deadline {
+            wait = wait.min(deadline.saturating_duration_since(Instant::now()));
+        }
+        if let Ok(Ok(request)) = timeout(wait, self.order_receiver.recv()).await {
+            self.admit(request);
+        }
+        None
+    }
+
+    pub fn process(&mut self, sequenced: Sequenced) -> Option<Trade> {
+        // everything that happens to one request once it's off the queue, returns the trade if it made one
+        self.round_orders += 1;
+        let stamp = sequenced.stamp;
+        let order = match &sequenced.request {
+            Request::Order(order) => order.clone(),
+            Request::Disconnect(player_name) => {
+                // `admit` applies these before they're stamped, this is only for a caller that stamps one itself
+                self.disconnect(&player_name.clone());
+                return None;
+            },
+            Request::Cancel(cancel) => {
+                if self.cancel_quote(cancel) {
+                    self.acknowledge(Ack::new(&sequenced, AckStatus::Cancelled));
+                    self.send_update(None);
+                } else {
+                    self.acknowledge(Ack::new(&sequenced, AckStatus::NothingToCancel));
+                }
+                return None;
             }
+        };
 
-            println!("");
-            println!("{}=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-={}", CL::Pink.get(), CL::End.get());
-            println!("{}=-=-=-=-=-=-=-=-=-=-=-=-=-=-= Round over! =-=-=-=-=-=-=-=-=-=-=-=-=-=-={}", CL::Pink.get(), CL::End.get());
-            println!("{}=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-={}", CL::Pink.get(), CL::End.get());
-            println!("");
-            
-            println!("=---= Game Details =---=");
-            println!("{} - Players: {}x{}", CL::Dull.get(), self.player_names.len(), CL::End.get());
-            println!("{} - Ante: {}{}", CL::Dull.get(), ante, CL::End.get());
-            println!("{} - Pot: {}{}", CL::Dull.get(), pot, CL::End.get());
-            println!("");
-            println!("=---= Card Count =---=");
-            for (suit, amount) in starting_inventory {
-                println!("{} - {:?} | {}x{}", CL::Dull.get(), suit, amount, CL::End.get());
+        if order.price == 0 { // No free lunches allowed
+            self.acknowledge(Ack::new(&sequenced, AckStatus::Rejected(RejectReason::ZeroPrice)));
+            return None;
+        }
+
+        // check the player is seated and has the inventory (or points) to back this order
+        if let Err(e) = self.validate_order(&order) {
+            info!(round = self.round, player = %order.player_name, seq = stamp.seq, suit = ?order.card, direction = ?order.direction, price = order.price, error = %e, "invalid order");
+            self.acknowledge(Ack::new(&sequenced, AckStatus::Rejected(e.reject_reason())));
+            return None;
+        }
+
+        if self.pull_uncovered_quotes() {
+            self.send_update(None);
+        }
+
+        if !self.prevent_self_trade(&sequenced, &order) {
+            return None;
+        }
+
+        debug!(round = self.round, player = %order.player_name, seq = stamp.seq, suit = ?order.card, direction = ?order.direction, price = order.price, queue = self.order_receiver.len() + self.throttle.queue.len(), "processing");
+
+        let (trade, status) = match self.match_order(stamp, &order) {
+            Ok(result) => result,
+            Err(e) => {
+                error!(round = self.round, player = %order.player_name, seq = stamp.seq, suit = ?order.card, error = %e, "unable to process order");
+                self.acknowledge(Ack::new(&sequenced, AckStatus::Rejected(e.reject_reason())));
+                return None;
             }
-            println!("{} - Common suit: {:?}{}", CL::Dull.get(), self.common_suit, CL::End.get());
-            println!("{} - Goal suit: {}{:?}{}{}", CL::Dull.get(), CL::LimeGreen.get(), self.goal_suit, CL::End.get(), CL::End.get());
-            println!("");
+        };
+        self.acknowledge(Ack::new(&sequenced, status));
 
-            self.round += 1;
+        if let Some(trade) = &trade {
+            self.round_trades.push(trade.clone());
 
-            // calculate the scores, each player is awared goal_suit * 10
-            // and the player with the most of the goal_suit is awarded 50
+            // =-= Reset all the Books =-= //
+            for book in self.books.values_mut() {
+                book.reset_quotes();
+            }
 
-            // get each players inventory and if add their points, simulatentously subtracting from pot
-            let mut winner: (PlayerName, usize) = (PlayerName::None, 0); // player_id, goal_cards
-            let mut tied_winnders: Vec<PlayerName> = Vec::new(); // player_ids
+            // =-= Drain the Order Receiver =-= //
+            // everything still queued was priced off the old books, so it goes, but its owner is told
+            let mut dropped = self.throttle.clear();
+            let mut disconnected = Vec::new();
+            let drain_amount = self.order_receiver.len();
+            for _ in 0..drain_amount {
+                match self.order_receiver.try_recv() {
+                    Ok(Some(Request::Disconnect(player_name))) => disconnected.push(player_name), // not priced off anything, still applies
+                    Ok(Some(request)) => dropped.push(self.stamp(request)),
+                    _ => {},
+                }
+            }
+            for sequenced in dropped.iter() {
+                self.acknowledge(Ack::new(sequenced, AckStatus::Dropped));
+            }
+            for player_name in disconnected.iter() {
+                self.disconnect(player_name);
+            }
+            self.metrics.record_trade(trade, dropped.len());
+            self.batch_remaining = 0;
 
-            println!("=---------------------------- Inventory ----------------------------=");
-            for player_name in &self.player_names {
-                let inventory = self.player_inventories.get(player_name).unwrap();
-                let player_points = self.player_points.get_mut(player_name).unwrap();
-                let goal_cards = match self.goal_suit {
-                    Card::Spade => inventory.spades,
-                    Card::Club => inventory.clubs,
-                    Card::Diamond => inventory.diamonds,
-                    Card::Heart => inventory.hearts,
-                };
+            self.run_audit(Checkpoint::Trade);
+        }
 
-                let (spade_color, club_color, diamond_color, heart_color) = match self.goal_suit {
-                    Card::Spade => (CL::LimeGreen.get(), CL::Dull.get(), CL::Dull.get(), CL::Dull.get()),
-                    Card::Club => (CL::Dull.get(), CL::LimeGreen.get(), CL::Dull.get(), CL::Dull.get()),
-                    Card::Diamond => (CL::Dull.get(), CL::Dull.get(), CL::LimeGreen.get(), CL::Dull.get()),
-                    Card::Heart => (CL::Dull.get(), CL::Dull.get(), CL::Dull.get(), CL::LimeGreen.get()),
-                };
+        self.print_game();
+
+        self.send_update(trade.clone());
+        trade
+    }
 
-                println!("{}{}{:?}{} |:| Spades: {}{}x{} | Clubs: {}{}x{} | Diamonds: {}{}x{} | Hearts: {}{}x{}{}", CL::Dull.get(), CL::DimLightBlue.get(), player_name, CL::Dull.get(), spade_color, inventory.spades, CL::Dull.get(), club_color, inventory.clubs, CL::Dull.get(), diamond_color, inventory.diamonds, CL::Dull.get(), heart_color, inventory.hearts, CL::End.get(), CL::End.get());
+    pub fn print_game(&self) {
+        let (spades_color, clubs_color, diamonds_color, hearts_color) = self.goal_suit.get_book_colors();
 
-                if goal_cards >= winner.1 {
-                    if goal_cards == winner.1 {
-                        tied_winnders.push(player_name.clone());
-                    } else {
-                        winner = (player_name.clone(), goal_cards);
-                        tied_winnders.clear();
+        console!("\n=---------------------------------------------------------------------------------=");
+
+        for (card, color) in [(Card::Spade, spades_color), (Card::Club, clubs_color), (Card::Diamond, diamonds_color), (Card::Heart, hearts_color)] {
+            let book = self.book(&card);
+            narrate!(
+                debug!(round = self.round, suit = ?card, bid = book.bid.price, bidder = %book.bid.player_name, ask = book.ask.price, asker = %book.ask.player_name, last_trade = ?book.last_trade, "book"),
+                "{}{:<10}{}|:| Bid: ({}{:?}{}, {:?}) | Ask: ({}{:?}{}, {:?}) |:|{} Last trade: {}{:?}{}", color.get(), card.plural(), CL::Dull.get(), CL::Green.get(), book.bid.price, CL::Dull.get(), book.bid.player_name, CL::PeachRed.get(), book.ask.price, CL::Dull.get(), book.ask.player_name, CL::Dull.get(), CL::DimLightBlue.get(), book.last_trade.unwrap_or_default(), CL::End.get()
+            );
+        }
+        
+        let points: Vec<(PlayerName, usize)> = self.player_names.iter()
+            .map(|player_name| (player_name.clone(), self.player_points.get(player_name).copied().unwrap_or(0)))
+            .collect();
+        let line = points.iter().map(|(player_name, player_points)| format!("{:?}: {}", player_name, player_points)).collect::<Vec<_>>().join(" | ");
+        narrate!(debug!(round = self.round, points = ?points, "points"), "{}Points    {}|:|{} {}{}", CL::DullGreen.get(), CL::Dull.get(), CL::DullGreen.get(), line, CL::End.get());
+        console!("=---------------------------------------------------------------------------------=\n");
+    }
+
+    pub fn send_update(&mut self, trade: Option<Trade>) {
+        let timestamp = self.engine_start.elapsed().as_micros() as u64;
+        self.tables.record_quotes(self.round, timestamp, &self.books);
+
+        let update = Update {
+            spades: self.book(&Card::Spade),
+            clubs: self.book(&Card::Club),
+            diamonds: self.book(&Card::Diamond),
+            hearts: self.book(&Card::Heart),
+            trade,
+        };
+        if let Err(e) = self.broadcast(Event::Update(Box::new(update))) {
+            error!(round = self.round, error = %e, "unable to send update event");
+        }
+        self.publish_snapshot();
+    }
+
+    pub fn publish_snapshot(&self) {
+        // the suits stay a secret until the round is over
+        let revealed = !self.trading && self.round_start.is_some();
+        let snapshot = Snapshot {
+            round: self.round,
+            trading: self.trading,
+            round_start: self.round_start,
+            round_duration: self.round_duration,
+            player_names: self.player_names.clone(),
+            books: self.books.clone(),
+            points: self.player_points.clone(),
+            inventories: self.player_inventories.clone(),
+            trades: self.round_trades[self.round_trades.len().saturating_sub(SNAPSHOT_TAPE)..].to_vec(),
+            trade_count: self.round_trades.len(),
+            orders: self.round_orders,
+            queue: self.order_receiver.len() + self.throttle.queue.len(),
+            common_suit: if revealed { Some(self.common_suit.clone()) } else { None },
+            goal_suit: if revealed { Some(self.goal_suit.clone()) } else { None },
+            point_changes: self.point_changes.clone(),
+            throttled: self.throttle.throttled.clone(),
+            metrics: self.metrics.clone(),
+        };
+        self.snapshot_sender.send_replace(snapshot);
+    }
+
+
+
+    pub fn collect_ante(&mut self, ante: usize) {
+        // seats pay in order, so a seeded game takes the same antes every time, and a broke seat only sits itself out
+        self.antes.clear();
+        for player_name in &self.player_names {
+            let Some(points) = self.player_points.get_mut(player_name) else { continue };
+            if *points < ante {
+                warn!(round = self.round, player = %player_name, points = *points, ante, "not enough points to play");
+                continue;
+            }
+            *points -= ante;
+            self.pot += ante;
+            self.antes.insert(player_name.clone(), ante);
+        }
+    }
+
+    pub fn deal(&mut self) -> HashMap<Card, usize> {
+        self.pick_new_common_suit();
+        let starting_inventory = self.get_new_inventories();
+        self.dealt_cards = self.held_cards();
+        self.tables.record_deal(self.round, &self.player_names, &self.player_inventories, &self.common_suit, &self.goal_suit);
+        starting_inventory
+    }
+
+    pub fn goal_cards(&self, player_name: &PlayerName) -> usize {
+        self.player_inventories.get(player_name).map(|inventory| inventory.get(&self.goal_suit)).unwrap_or(0)
+    }
+
+    pub fn settle_round(&mut self) {
+        // calculate the scores, each player is awared goal_suit * 10
+        // and whoever holds the most of the goal_suit takes what's left of the pot, split evenly on a tie
+        // (the first tied seat taking the odd points), so nothing is left in the pot for the next round
+
+        self.payouts = self.player_names.iter().map(|player_name| (player_name.clone(), Payout::default())).collect();
+
+        console!("=---------------------------- Inventory ----------------------------=");
+        for player_name in &self.player_names {
+            let inventory = self.player_inventories.get(player_name).copied().unwrap_or_default();
+            let goal_cards = self.goal_cards(player_name);
+
+            let (spade_color, club_color, diamond_color, heart_color) = match self.goal_suit {
+                Card::Spade => (CL::LimeGreen.get(), CL::Dull.get(), CL::Dull.get(), CL::Dull.get()),
+                Card::Club => (CL::Dull.get(), CL::LimeGreen.get(), CL::Dull.get(), CL::Dull.get()),
+                Card::Diamond => (CL::Dull.get(), CL::Dull.get(), CL::LimeGreen.get(), CL::Dull.get()),
+                Card::Heart => (CL::Dull.get(), CL::Dull.get(), CL::Dull.get(), CL::LimeGreen.get()),
+            };
+
+            narrate!(
+                info!(round = self.round, player = %player_name, spades = inventory.spades, clubs = inventory.clubs, diamonds = inventory.diamonds, hearts = inventory.hearts, goal_cards, "settled hand"),
+                "{}{}{:?}{} |:| Spades: {}{}x{} | Clubs: {}{}x{} | Diamonds: {}{}x{} | Hearts: {}{}x{}{}", CL::Dull.get(), CL::DimLightBlue.get(), player_name, CL::Dull.get(), spade_color, inventory.spades, CL::Dull.get(), club_color, inventory.clubs, CL::Dull.get(), diamond_color, inventory.diamonds, CL::Dull.get(), heart_color, inventory.hearts, CL::End.get(), CL::End.get()
+            );
+
+            // the pot can come up short if someone couldn't make the ante
+            let payout = (goal_cards * GOAL_CARD_VALUE).min(self.pot);
+            if let Some(player_points) = self.player_points.get_mut(player_name) {
+                *player_points += payout;
+                self.pot -= payout;
+                self.payouts.entry(player_name.clone()).or_default().goal = payout;
+            }
+        }
+        console!();
+
+        let most_goal_cards = self.player_names.iter().map(|player_name| self.goal_cards(player_name)).max().unwrap_or(0);
+        let winners: Vec<PlayerName> = self.player_names.iter()
+            .filter(|player_name| self.goal_cards(player_name) == most_goal_cards)
+            .cloned().collect();
+
+        console!("=----------------------------- Results -----------------------------=");
+        match winners.len() {
+            0 => {},
+            1 => {
+                narrate!(
+                    info!(round = self.round, player = %winners[0], goal_cards = most_goal_cards, pot = self.pot, "wins the pot"),
+                    "{}[+] Player '{:?}' wins the whole pot of {} points{}", CL::Green.get(), winners[0], self.pot, CL::End.get()
+                );
+                if let Some(winner_points) = self.player_points.get_mut(&winners[0]) {
+                    *winner_points += self.pot;
+                    self.payouts.entry(winners[0].clone()).or_default().bonus = self.pot;
+                    self.pot = 0;
+                }
+            },
+            tied => {
+                // whatever doesn't split evenly goes to the first of the tied seats, so the pot is always emptied
+                let (split, odd) = (self.pot / tied, self.pot % tied);
+                console!("{}[+] Players tie for the pot of {} points{}\n", CL::Teal.get(), self.pot, CL::End.get());
+                console!("{}------ Tied Players ------{}", CL::Dull.get(), CL::End.get());
+                for (idx, player_name) in winners.iter().enumerate() {
+                    let share = if idx == 0 { split + odd } else { split };
+                    narrate!(
+                        info!(round = self.round, player = %player_name, goal_cards = most_goal_cards, pot = self.pot, share, tied, "ties for the pot"),
+                        "{}{}{:?}{} | Goal Cards: {}x | Points: {}+{}x{}{}", CL::Dull.get(), CL::DimLightBlue.get(), player_name, CL::Dull.get(), most_goal_cards, CL::LimeGreen.get(), share, CL::End.get(), CL::End.get()
+                    );
+                    if let Some(player_points) = self.player_points.get_mut(player_name) {
+                        *player_points += share;
+                        self.pot -= share;
+                        self.payouts.entry(player_name.clone()).or_default().bonus = share;
                     }
                 }
+            },
+        }
+        console!();
+    }
 
-                *player_points += goal_cards * 10;
-                pot -= goal_cards * 10;
+    pub fn round_report(&self) -> RoundReport {
+        RoundReport::new(self.round, self.common_suit.clone(), self.goal_suit.clone(), &self.player_names, &self.antes, &self.round_trades, &self.payouts)
+    }
+
+    pub async fn start(&mut self) {
+        loop {
+            let ante = 200 / self.player_names.len();
+
+            narrate!(
+                info!(round = self.round, players = self.player_names.len(), ante, "round starting"),
+                "{}==================== ROUND {} ===================={}", CL::Purple.get(), self.round, CL::End.get()
+            );
+            console!();
+            console!("=---= Game Details =---=");
+            console!("{} - Players: {}x{}", CL::Dull.get(), self.player_names.len(), CL::End.get());
+            console!("{} - Ante: {}{}", CL::Dull.get(), ante, CL::End.get());
+            console!("{} - Pot: 200{}", CL::Dull.get(), CL::End.get());
+            console!();
+            
+            let initial_points = self.player_points.clone();
+            self.round_start = None; // the new hands are dealt before trading opens, nothing gets revealed in between
+            self.collect_ante(ante);
+            let starting_inventory = self.deal();
+
+            console!("{} - Common suit: {:?}{}", CL::Dull.get(), self.common_suit, CL::End.get());
+            console!("{} - Goal suit: {}{:?}{}{}", CL::Dull.get(), CL::LimeGreen.get(), self.goal_suit, CL::End.get(), CL::End.get());
+            console!();
+
+            narrate!(
+                info!(round = self.round, pot = self.pot, common_suit = ?self.common_suit, goal_suit = ?self.goal_suit, "dealt"),
+                "{}[+] Dealing cards...{}\n", CL::DimLightBlue.get(), CL::End.get()
+            );
+            
+            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await; // give the players a little bit to get ready
+            
+            self.publish_snapshot(); // ahead of the deal, so a gateway bot that connects too late for it still gets its hand
+            if let Err(e) = self.broadcast(Event::DealCards(self.player_inventories.clone())) {
+                error!(round = self.round, error = %e, "unable to send deal cards event");
             }
-            println!("");
 
-            // if there's one winner, award them the pot
-            // if there's a tie, split the pot evenly between the winners
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await; // give the players some time to order their cards
 
-            println!("=----------------------------- Results -----------------------------=");
-            if tied_winnders.is_empty() {
-                println!("{}[+] Player '{:?}' wins the whole pot of {} points{}", CL::Green.get(), winner.0, pot, CL::End.get());
-                let winner_points = self.player_points.get_mut(&winner.0).unwrap();
-                *winner_points += pot;
-            } else {
-                let split = pot / (tied_winnders.len() + 1);
-                println!("{}[+] Players tie for the pot of {} points{}\n", CL::Teal.get(), pot, CL::End.get());
-                println!("{}------ Tied Players ------{}", CL::Dull.get(), CL::End.get());
-                println!("{}{}{:?}{} | Goal Cards: {}x | Points: {}+{}x{}{}", CL::Dull.get(), CL::DimLightBlue.get(), winner.0, CL::Dull.get(), winner.1, CL::LimeGreen.get(), split, CL::End.get(), CL::End.get());
-                for player_name in tied_winnders {
-                    println!("{}{}{:?}{} | Goal Cards: {}x | Points: {}+{}x{}{}", CL::Dull.get(), CL::DimLightBlue.get(), player_name, CL::Dull.get(), winner.1, CL::LimeGreen.get(), split, CL::End.get(), CL::End.get());
-                    let player_points = self.player_points.get_mut(&player_name).unwrap();
-                    *player_points += split;
+            // quotes left over from last round may not be backed by the new hands
+            self.pull_uncovered_quotes();
+
+            // send out the book
+            self.send_update(None);
+
+            let start = Instant::now();
+            self.round_start = Some(start);
+            self.trading = true;
+            self.round_trades.clear();
+            self.round_orders = 0;
+            self.throttle.reset_round();
+            self.metrics.reset_round();
+            let round_metrics = self.metrics.clone(); // the round's summary is everything since now
+            self.batch_deadline = None;
+            self.batch_remaining = 0;
+            self.publish_snapshot();
+
+            while start.elapsed() < self.round_duration {
+
+                if let Some(sequenced) = self.next_request(start).await {
+                    let wait = self.engine_start.elapsed().saturating_sub(Duration::from_micros(sequenced.stamp.timestamp));
+                    self.metrics.record_queue(self.order_receiver.len(), self.throttle.queue.len());
+                    let processing = std::time::Instant::now(); // the real clock, batch games pause tokio's
+                    self.process(sequenced);
+                    self.metrics.record_processing(processing.elapsed(), wait);
                 }
+            } 
+
+            // =-= End the Round =-= //
+            self.trading = false;
+            let end_round = Event::EndRound;
+            if let Err(e) = self.broadcast(end_round) {
+                error!(round = self.round, error = %e, "unable to send end round event");
+            }
+
+            console!();
+            console!("{}=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-={}", CL::Pink.get(), CL::End.get());
+            narrate!(
+                info!(round = self.round, pot = self.pot, trades = self.round_trades.len(), orders = self.round_orders, "round over"),
+                "{}=-=-=-=-=-=-=-=-=-=-=-=-=-=-= Round over! =-=-=-=-=-=-=-=-=-=-=-=-=-=-={}", CL::Pink.get(), CL::End.get()
+            );
+            console!("{}=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-={}", CL::Pink.get(), CL::End.get());
+            console!();
+            
+            console!("=---= Game Details =---=");
+            console!("{} - Players: {}x{}", CL::Dull.get(), self.player_names.len(), CL::End.get());
+            console!("{} - Ante: {}{}", CL::Dull.get(), ante, CL::End.get());
+            console!("{} - Pot: {}{}", CL::Dull.get(), self.pot, CL::End.get());
+            console!();
+            console!("=---= Card Count =---=");
+            for (suit, amount) in starting_inventory {
+                narrate!(info!(round = self.round, suit = ?suit, cards = amount, "card count"), "{} - {:?} | {}x{}", CL::Dull.get(), suit, amount, CL::End.get());
+            }
+            console!("{} - Common suit: {:?}{}", CL::Dull.get(), self.common_suit, CL::End.get());
+            console!("{} - Goal suit: {}{:?}{}{}", CL::Dull.get(), CL::LimeGreen.get(), self.goal_suit, CL::End.get(), CL::End.get());
+            console!();
+            console!("=---= Throttling =---=");
+            for player_name in &self.player_names {
+                let throttled = self.throttle.throttled.get(player_name).copied().unwrap_or(0);
+                narrate!(info!(round = self.round, player = %player_name, throttled, "throttled"), "{} - {:?} | {}x{}", CL::Dull.get(), player_name, throttled, CL::End.get());
             }
-            println!("");
+            console!();
+            console!("=---= Engine =---=");
+            for line in self.metrics.since(&round_metrics).summary() {
+                console!("{}{}{}", CL::Dull.get(), line, CL::End.get());
+            }
+            console!();
+
+            self.settle_round();
 
-            println!("=-------------------------- Updated Points -------------------------=");
+            console!("=-------------------------- Updated Points -------------------------=");
             let mut inventory_string = String::from("");
+            let mut points = Vec::new();
             for player_name in &self.player_names {
-                let initial_points = initial_points.get(player_name).unwrap();
-                let player_points = self.player_points.get(player_name).unwrap();
-                let point_change: i32 = *player_points as i32 - *initial_points as i32;
+                let initial_points = initial_points.get(player_name).copied().unwrap_or(0);
+                let player_points = self.player_points.get(player_name).copied().unwrap_or(0);
+                let point_change: i64 = player_points as i64 - initial_points as i64;
 
                 let change_color = match point_change {
                     x if x > 0 => CL::Green.get(),
@@ -427,11 +793,29 @@ impl MatchMaker {
                     _ => CL::Dull.get(),
                 };
 
+                points.push((player_name.clone(), player_points, point_change));
                 inventory_string += &format!("{:?}: {} {}({}){} | ", player_name, player_points, change_color, point_change, CL::Dull.get());
+                self.point_changes.insert(player_name.clone(), point_change);
             }
             inventory_string.truncate(inventory_string.len() - 3);
-            println!("{}{}{}", CL::Dull.get(), inventory_string, CL::End.get());
-            println!("");
+            narrate!(info!(round = self.round, points = ?points, "updated points"), "{}{}{}", CL::Dull.get(), inventory_string, CL::End.get());
+            console!();
+
+            self.run_audit(Checkpoint::Settlement);
+
+            let report = self.round_report();
+            if let Err(e) = self.tables.record_round(&report, &self.round_trades, &self.player_points) {
+                error!(round = self.round, error = %e, "unable to write the tables");
+            }
+            self.analytics.record(report);
+
+            // published before the count moves on, the reveal is of the round that was just settled
+            self.publish_snapshot();
+            self.round += 1;
+
+            if self.rounds.is_some_and(|rounds| self.round >= rounds) {
+                return;
+            }
 
             tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
 
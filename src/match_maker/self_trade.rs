@@ -0,0 +1,79 @@
+use super::{MatchMaker, Sequenced, Order, Cancel, Direction, Ack, AckStatus, RejectReason};
+use std::str::FromStr;
+use tracing::info;
+
+
+// =-= Self-Trade Prevention =-= //
+// - an incoming order that would match the same player's own resting quote never trades, how it's stopped depends on the mode
+// - Reject: the incoming order is turned away with `RejectReason::SelfTrade`
+// - CancelIncoming: the incoming order is cancelled, the resting quote stays put
+// - CancelResting: the resting quote is pulled and the incoming order carries on, resting in its place if it improves the book
+// - Allow: the old behaviour, players can trade with themselves
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    Allow,
+    Reject,
+    CancelIncoming,
+    CancelResting,
+}
+
+impl FromStr for SelfTradePrevention {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "off" | "allow" => Ok(SelfTradePrevention::Allow),
+            "reject" => Ok(SelfTradePrevention::Reject),
+            "cancel-incoming" => Ok(SelfTradePrevention::CancelIncoming),
+            "cancel-resting" => Ok(SelfTradePrevention::CancelResting),
+            _ => Err(format!("unknown mode '{}', pick from reject, cancel-incoming, cancel-resting or off", text)),
+        }
+    }
+}
+
+
+impl MatchMaker {
+    pub fn prevent_self_trade(&mut self, sequenced: &Sequenced, order: &Order) -> bool {
+        // returns whether the incoming order should carry on to the book
+        let Some(book) = self.books.get(&order.card) else { return true }; // `match_order` reports the missing book
+        let (resting, resting_direction) = match order.direction {
+            Direction::Buy => (&book.ask, Direction::Sell),
+            Direction::Sell => (&book.bid, Direction::Buy),
+        };
+        let crosses = match order.direction {
+            Direction::Buy => order.price >= resting.price,
+            Direction::Sell => order.price <= resting.price,
+        };
+        if !crosses || resting.player_name != order.player_name {
+            return true;
+        }
+
+        match self.self_trade_prevention {
+            SelfTradePrevention::Allow => true,
+            SelfTradePrevention::Reject => {
+                self.acknowledge(Ack::new(sequenced, AckStatus::Rejected(RejectReason::SelfTrade)));
+                false
+            },
+            SelfTradePrevention::CancelIncoming => {
+                info!(round = self.round, player = %order.player_name, seq = sequenced.stamp.seq, suit = ?order.card, direction = ?order.direction, price = order.price, "self-trade, cancelled incoming");
+                self.acknowledge(Ack::new(sequenced, AckStatus::SelfTradeCancelled));
+                false
+            },
+            SelfTradePrevention::CancelResting => {
+                let resting_ack = Ack {
+                    player_name: resting.player_name.clone(),
+                    seq: resting.seq,
+                    timestamp: sequenced.stamp.timestamp,
+                    card: order.card.clone(),
+                    direction: resting_direction.clone(),
+                    price: Some(resting.price),
+                    status: AckStatus::SelfTradeCancelled,
+                };
+                self.cancel_quote(&Cancel { player_name: order.player_name.clone(), direction: resting_direction, card: order.card.clone() });
+                self.acknowledge(resting_ack);
+                true
+            },
+        }
+    }
+}
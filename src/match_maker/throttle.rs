@@ -0,0 +1,179 @@
+use super::{Request, Sequenced, Ack, AckStatus, RejectReason, PlayerName};
+use tokio::time::{Duration, Instant};
+use std::collections::{HashMap, VecDeque};
+
+
+// =-= Throttle =-= //
+// - every request passes through here between the kanal queue and the book, so no single player can flood the match maker
+// - max outstanding: how many of a player's requests can be waiting in the queue at once, anything past that is rejected on arrival
+// - orders per second: a token bucket per player (with a little burst), cancels are free since they only ever take risk off
+// - an order that's over the rate is either rejected straight away or held back until the player has a token again
+// - off by default, `--max-rate` and `--max-outstanding` turn the limits on
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleAction {
+    Reject,
+    Delay,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThrottleConfig {
+    pub orders_per_second: f64,
+    pub burst: f64,
+    pub max_outstanding: usize,
+    pub action: ThrottleAction,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        // no limits until they're asked for
+        Self {
+            orders_per_second: f64::INFINITY,
+            burst: f64::INFINITY,
+            max_outstanding: usize::MAX,
+            action: ThrottleAction::Reject,
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(burst: f64, now: Instant) -> Self {
+        Self { tokens: burst, last_refill: now }
+    }
+
+    pub fn refill(&mut self, config: &ThrottleConfig, now: Instant) {
+        if config.orders_per_second.is_infinite() {
+            self.tokens = config.burst;
+            self.last_refill = now;
+            return;
+        }
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.orders_per_second).min(config.burst);
+        self.last_refill = now;
+    }
+
+    pub fn wait(&self, config: &ThrottleConfig) -> Option<Duration> {
+        // how long until the next whole token, None if it's never coming
+        if self.tokens >= 1.0 {
+            return Some(Duration::ZERO);
+        }
+        if config.orders_per_second <= 0.0 || config.orders_per_second.is_nan() {
+            return None;
+        }
+        Some(Duration::from_secs_f64((1.0 - self.tokens) / config.orders_per_second))
+    }
+}
+
+
+pub struct Throttle {
+    pub config: ThrottleConfig,
+    pub buckets: HashMap<PlayerName, TokenBucket>,
+    pub queue: VecDeque<(Sequenced, bool)>, // whether it's already been held back once
+    pub throttled: HashMap<PlayerName, usize>, // rejected or delayed this round
+}
+
+impl Throttle {
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            buckets: HashMap::new(),
+            queue: VecDeque::new(),
+            throttled: HashMap::new(),
+        }
+    }
+
+    pub fn outstanding(&self, player_name: &PlayerName) -> usize {
+        self.queue.iter().filter(|(sequenced, _)| sequenced.request.player_name() == player_name).count()
+    }
+
+    pub fn enqueue(&mut self, sequenced: Sequenced) -> Result<(), Ack> {
+        let player_name = sequenced.request.player_name();
+        if self.outstanding(player_name) >= self.config.max_outstanding {
+            *self.throttled.entry(player_name.clone()).or_insert(0) += 1;
+            return Err(Ack::new(&sequenced, AckStatus::Rejected(RejectReason::TooManyOutstanding)));
+        }
+        self.queue.push_back((sequenced, false));
+        Ok(())
+    }
+
+    pub fn has_token(&mut self, player_name: &PlayerName, now: Instant) -> bool {
+        let config = self.config;
+        let bucket = self.buckets.entry(player_name.clone()).or_insert_with(|| TokenBucket::new(config.burst, now));
+        bucket.refill(&config, now);
+        bucket.tokens >= 1.0
+    }
+
+    pub fn take_token(&mut self, player_name: &PlayerName) {
+        if let Some(bucket) = self.buckets.get_mut(player_name) {
+            bucket.tokens -= 1.0;
+        }
+    }
+
+    pub fn next(&mut self, now: Instant) -> Option<Result<Sequenced, Ack>> {
+        // the next request allowed through, None if there isn't one yet
+        match self.config.action {
+            ThrottleAction::Reject => {
+                let (sequenced, _) = self.queue.pop_front()?;
+                if let Request::Order(order) = &sequenced.request {
+                    if !self.has_token(&order.player_name, now) {
+                        *self.throttled.entry(order.player_name.clone()).or_insert(0) += 1;
+                        return Some(Err(Ack::new(&sequenced, AckStatus::Rejected(RejectReason::RateLimited))));
+                    }
+                    self.take_token(&order.player_name);
+                }
+                Some(Ok(sequenced))
+            },
+            ThrottleAction::Delay => {
+                // skip past anyone who's out of tokens, their orders keep their place in line until they can go
+                for idx in 0..self.queue.len() {
+                    let player_name = match &self.queue[idx].0.request {
+                        Request::Order(order) => order.player_name.clone(),
+                        Request::Cancel(_) | Request::Disconnect(_) => return self.queue.remove(idx).map(|(sequenced, _)| Ok(sequenced)),
+                    };
+                    if self.has_token(&player_name, now) {
+                        self.take_token(&player_name);
+                        return self.queue.remove(idx).map(|(sequenced, _)| Ok(sequenced));
+                    }
+                    if !self.queue[idx].1 { // only count a delayed order the first time it's held back
+                        self.queue[idx].1 = true;
+                        *self.throttled.entry(player_name).or_insert(0) += 1;
+                    }
+                }
+                None
+            },
+        }
+    }
+
+    pub fn next_ready(&self) -> Option<Duration> {
+        // how long until a held back order can go, None if nothing is waiting on a token
+        self.queue.iter()
+            .filter_map(|(sequenced, _)| self.buckets.get(sequenced.request.player_name()))
+            .filter_map(|bucket| bucket.wait(&self.config))
+            .min()
+    }
+
+    pub fn clear(&mut self) -> Vec<Sequenced> {
+        // hands back whatever was still waiting, so it can be told it was dropped
+        self.queue.drain(..).map(|(sequenced, _)| sequenced).collect()
+    }
+
+    pub fn remove(&mut self, player_name: &PlayerName) -> Vec<Sequenced> {
+        // hands back whatever one player still had waiting, like `clear` does for everyone
+        let (removed, kept): (VecDeque<_>, VecDeque<_>) = self.queue.drain(..).partition(|(sequenced, _)| sequenced.request.player_name() == player_name);
+        self.queue = kept;
+        removed.into_iter().map(|(sequenced, _)| sequenced).collect()
+    }
+
+    pub fn reset_round(&mut self) {
+        self.queue.clear();
+        self.buckets.clear();
+        self.throttled.clear();
+    }
+}
@@ -1,12 +1,14 @@
 use super::PlayerName;
+use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Quote {
     pub price: usize,
     pub player_name: PlayerName,
+    pub seq: u64, // the order that set this quote, 0 when nobody's quoting
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Book {
     pub bid: Quote,
     pub ask: Quote,
@@ -18,10 +20,12 @@ impl Book {
         self.bid = Quote {
             price: 0,
             player_name: PlayerName::None,
+            seq: 0,
         };
         self.ask = Quote {
             price: 99,
             player_name: PlayerName::None,
+            seq: 0,
         };
     }
 
@@ -30,16 +34,24 @@ impl Book {
     }
 }
 
+impl Default for Book {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Book {
     pub fn new() -> Self {
         Self {
             bid: Quote {
                 price: 0,
                 player_name: PlayerName::None,
+                seq: 0,
             },
             ask: Quote {
                 price: 99,
                 player_name: PlayerName::None,
+                seq: 0,
             },
             last_trade: None,
         }
@@ -0,0 +1,40 @@
+use super::{Card, RejectReason, PlayerName};
+use std::fmt;
+
+
+// =-= EngineError =-= //
+// - everything that can go wrong while the match maker applies a request
+// - nothing is half applied when one of these comes back, the request is rejected and the game carries on
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    UnknownPlayer(PlayerName),
+    UnknownBook(Card),
+    InsufficientInventory(Card),
+    InsufficientPoints { player_name: PlayerName, needed: usize, available: usize },
+    Overflow,
+}
+
+impl EngineError {
+    pub fn reject_reason(&self) -> RejectReason {
+        match self {
+            EngineError::InsufficientInventory(_) => RejectReason::NoInventory,
+            EngineError::InsufficientPoints { .. } => RejectReason::InsufficientPoints,
+            _ => RejectReason::Invalid,
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EngineError::UnknownPlayer(player_name) => write!(f, "{:?} isn't seated at this table", player_name),
+            EngineError::UnknownBook(card) => write!(f, "there's no book for {}", card.plural()),
+            EngineError::InsufficientInventory(card) => write!(f, "no {} left to give up", card.plural()),
+            EngineError::InsufficientPoints { player_name, needed, available } => write!(f, "{:?} needs {} points but only has {}", player_name, needed, available),
+            EngineError::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
@@ -1,17 +1,22 @@
-use super::{Card, Book, Inventory, PlayerName};
+use super::{Card, Book, Inventory, Direction, Request, Sequenced, PlayerName};
 use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
     pub card: Card,
     pub price: usize,
     pub buyer: PlayerName,
     pub seller: PlayerName,
+    pub seq: u64, // the aggressing order
+    pub resting_seq: u64, // the quote it hit
+    pub aggressor: Direction, // the side that crossed the spread, the other side was resting
+    pub timestamp: u64, // engine microseconds, see `Stamp`
 }
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Update {
     pub spades: Book,
     pub clubs: Book,
@@ -20,9 +25,61 @@ pub struct Update {
     pub trade: Option<Trade>,
 }
 
+// why the match maker turned a request away
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    RateLimited, // over the player's orders per second
+    TooManyOutstanding, // too many of the player's requests already waiting in the queue
+    ZeroPrice, // no free lunches
+    NoInventory, // selling a card we don't have
+    SelfTrade, // would have matched our own quote
+    InsufficientPoints, // bidding more than we have
+    Invalid, // anything else the match maker couldn't apply, see `EngineError`
+}
+
+// what became of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AckStatus {
+    Filled, // traded straight away
+    Resting, // now the best bid/ask
+    NotImproved, // didn't beat the quote already there, so it's gone
+    Cancelled,
+    NothingToCancel,
+    Dropped, // still queued when a trade reset the books
+    SelfTradeCancelled, // pulled by self-trade prevention, see `SelfTradePrevention`
+    Uncovered, // a resting quote pulled because we no longer hold the card (or the points) behind it
+    Rejected(RejectReason),
+}
+
+// the match maker answers every request it takes off the queue with one of these
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ack {
+    pub player_name: PlayerName,
+    pub seq: u64,
+    pub timestamp: u64,
+    pub card: Card,
+    pub direction: Direction,
+    pub price: Option<usize>, // cancels don't have one
+    pub status: AckStatus,
+}
+
+impl Ack {
+    pub fn new(sequenced: &Sequenced, status: AckStatus) -> Self {
+        let (player_name, card, direction, price) = match &sequenced.request {
+            Request::Order(order) => (order.player_name.clone(), order.card.clone(), order.direction.clone(), Some(order.price)),
+            Request::Cancel(cancel) => (cancel.player_name.clone(), cancel.card.clone(), cancel.direction.clone(), None),
+            Request::Disconnect(_) => unreachable!("a disconnect is applied as it's taken off the queue, it's never stamped or acknowledged"),
+        };
+        Self { player_name, seq: sequenced.stamp.seq, timestamp: sequenced.stamp.timestamp, card, direction, price, status }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Event {
-    Update(Update),
+    Update(Box<Update>),
     DealCards(HashMap<PlayerName, Inventory>),
     EndRound,
+    Ack(Ack), // only meant for `player_name`, everyone else can ignore it
 }
\ No newline at end of file
@@ -0,0 +1,196 @@
+use super::{Card, CardTracker, Inventory};
+use std::collections::HashMap;
+
+
+// =-= FairValue =-= //
+// - there are 12 possible decks: 4 common suits x 3 choices for which other suit gets 8 cards
+// - they aren't equally likely, `MatchMaker::get_new_inventories` flips a coin down the other suits in order
+// (see `Card::get_other_cards`), so the first gets the 8 half the time and the other two a quarter each
+// - the goal suit is always the same color as the common suit, so the deck decides everything
+// - given the cards we've seen come out of the deal (our hand + whatever the tape proved others were dealt),
+// each deck is weighted by how likely it is to produce those cards (multivariate hypergeometric)
+// - a card is then worth 10 if it's the goal suit, plus whatever it changes about our share of the majority bonus
+
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+
+#[derive(Debug, Clone)]
+pub struct Deck {
+    pub common_suit: Card,
+    pub eight_suit: Card,
+    pub probability: f64,
+}
+
+impl Deck {
+    pub fn count(&self, card: &Card) -> usize {
+        if *card == self.common_suit {
+            12
+        } else if *card == self.eight_suit {
+            8
+        } else {
+            10
+        }
+    }
+
+    pub fn goal_suit(&self) -> Card {
+        self.common_suit.get_goal_suit()
+    }
+}
+
+
+pub fn deck_prior(common_suit: &Card, eight_suit: &Card) -> f64 {
+    // the chance the match maker deals this deck before anything is seen
+    let (goal_suit, suit_1, suit_2) = common_suit.get_other_cards();
+    let eight_odds = if *eight_suit == suit_1 {
+        0.5
+    } else if *eight_suit == suit_2 || *eight_suit == goal_suit {
+        0.25
+    } else {
+        0.0
+    };
+    eight_odds / 4.0
+}
+
+fn choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let mut result = 1.0;
+    for i in 0..k {
+        result *= (n - i) as f64 / (i + 1) as f64;
+    }
+    result
+}
+
+pub fn deck_probabilities(seen: &Inventory) -> Vec<Deck> {
+    let mut decks = Vec::new();
+    for common_suit in SUITS {
+        for eight_suit in SUITS {
+            if eight_suit == common_suit {
+                continue;
+            }
+            let probability = deck_prior(&common_suit, &eight_suit);
+            let mut deck = Deck { common_suit: common_suit.clone(), eight_suit, probability };
+            for card in SUITS.iter() {
+                deck.probability *= choose(deck.count(card), seen.get(card));
+            }
+            decks.push(deck);
+        }
+    }
+
+    let total: f64 = decks.iter().map(|deck| deck.probability).sum();
+    for deck in decks.iter_mut() {
+        // nothing we've seen fits any deck (a bad tape), so fall back to the prior
+        deck.probability = if total > 0.0 { deck.probability / total } else { deck_prior(&deck.common_suit, &deck.eight_suit) };
+    }
+    decks
+}
+
+
+#[derive(Debug, Clone)]
+pub struct FairValue {
+    pub decks: Vec<Deck>,
+    pub holdings: Inventory,
+    pub rivals: HashMap<Card, f64>, // estimated holdings of the strongest rival per suit
+    pub pot: usize,
+}
+
+impl FairValue {
+    pub fn new(seen: Inventory, holdings: Inventory, rivals: HashMap<Card, f64>, pot: usize) -> Self {
+        Self {
+            decks: deck_probabilities(&seen),
+            holdings,
+            rivals,
+            pot,
+        }
+    }
+
+    pub fn from_tracker(tracker: &CardTracker) -> Self {
+        // every card the tape proved was dealt came from a different hand than ours, so they add up
+        let mut seen = Inventory::new();
+        for dealt in tracker.dealt.values() {
+            for card in SUITS.iter() {
+                for _ in 0..dealt.get(card) {
+                    let _ = seen.change(card.clone(), true);
+                }
+            }
+        }
+
+        let holdings = tracker.known.get(&tracker.name).copied().unwrap_or_default();
+        let players = tracker.known.len().max(1);
+        let pot = 200 / players * players; // same ante maths as the match maker
+        let decks = deck_probabilities(&seen);
+
+        // - split each suit's unaccounted-for cards across the hidden part of every rival's hand
+        // - the rival that ends up with the most is the one we'd have to beat for the bonus
+        let hidden: usize = tracker.known.keys()
+            .filter(|player_name| **player_name != tracker.name)
+            .map(|player_name| tracker.unknown_cards(player_name))
+            .sum();
+
+        let mut rivals = HashMap::new();
+        for card in SUITS.iter() {
+            let expected_total: f64 = decks.iter().map(|deck| deck.probability * deck.count(card) as f64).sum();
+            let accounted: usize = tracker.known.values().map(|inventory| inventory.get(card)).sum();
+            let unaccounted = (expected_total - accounted as f64).max(0.0);
+
+            let mut strongest: f64 = 0.0;
+            for player_name in tracker.known.keys() {
+                if *player_name == tracker.name {
+                    continue;
+                }
+                let share = if hidden > 0 { tracker.unknown_cards(player_name) as f64 / hidden as f64 } else { 0.0 };
+                let estimate = tracker.min_holding(player_name, card) as f64 + unaccounted * share;
+                strongest = strongest.max(estimate);
+            }
+            rivals.insert(card.clone(), strongest);
+        }
+
+        Self { decks, holdings, rivals, pot }
+    }
+
+    pub fn goal_probability(&self, card: &Card) -> f64 {
+        self.decks.iter()
+            .filter(|deck| deck.goal_suit() == *card)
+            .map(|deck| deck.probability)
+            .sum()
+    }
+
+    pub fn expected_value(&self, card: &Card, held: usize) -> f64 {
+        // what holding `held` of this suit is worth at settlement
+        let rival = self.rivals.get(card).copied().unwrap_or(0.0).round() as usize;
+        let share = match held {
+            0 => 0.0,
+            x if x > rival => 1.0,
+            x if x == rival => 0.5,
+            _ => 0.0,
+        };
+
+        let mut value = 0.0;
+        for deck in self.decks.iter().filter(|deck| deck.goal_suit() == *card) {
+            let bonus = self.pot.saturating_sub(deck.count(card) * 10) as f64;
+            value += deck.probability * (held as f64 * 10.0 + bonus * share);
+        }
+        value
+    }
+
+    pub fn buy_value(&self, card: &Card) -> f64 {
+        let held = self.holdings.get(card);
+        self.expected_value(card, held + 1) - self.expected_value(card, held)
+    }
+
+    pub fn sell_value(&self, card: &Card) -> f64 {
+        let held = self.holdings.get(card);
+        if held == 0 {
+            return 0.0;
+        }
+        self.expected_value(card, held) - self.expected_value(card, held - 1)
+    }
+
+    pub fn quote(&self, card: &Card, edge: f64) -> (usize, usize) {
+        // (bid, ask) around what the next card is worth to us, both stay within the book's 99 cap with room for the spread
+        let bid = (self.buy_value(card) - edge).floor().clamp(0.0, 98.0) as usize;
+        let ask = (self.sell_value(card) + edge).ceil().clamp(1.0, 99.0) as usize;
+        (bid, ask.max(bid + 1))
+    }
+}
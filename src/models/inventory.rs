@@ -1,7 +1,8 @@
-use super::{Card};
+use super::{Card, EngineError};
+use serde::{Serialize, Deserialize};
 
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Inventory {
     pub spades: usize,
     pub clubs: usize,
@@ -9,6 +10,12 @@ pub struct Inventory {
     pub hearts: usize,
 }
 
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Inventory {
     pub fn new() -> Self {
         Self {
@@ -30,45 +37,20 @@ impl Inventory {
         }
     }
 
-    pub fn change(&mut self, card: Card, add: bool) {
-        match card {
-            Card::Spade => {
-                let new_amount: usize;
-                if add {
-                    new_amount = self.spades + 1;
-                } else {
-                    new_amount = self.spades - 1;
-                }
-                self.spades = new_amount;
-            },
-            Card::Club => {
-                let new_amount: usize;
-                if add {
-                    new_amount = self.clubs + 1;
-                } else {
-                    new_amount = self.clubs - 1;
-                }
-                self.clubs = new_amount;
-            },
-            Card::Diamond => {
-                let new_amount: usize;
-                if add {
-                    new_amount = self.diamonds + 1;
-                } else {
-                    new_amount = self.diamonds - 1;
-                }
-                self.diamonds = new_amount;
-            },
-            Card::Heart => {
-                let new_amount: usize;
-                if add {
-                    new_amount = self.hearts + 1;
-                } else {
-                    new_amount = self.hearts - 1;
-                }
-                self.hearts = new_amount;
-            },
-        }
+    pub fn change(&mut self, card: Card, add: bool) -> Result<(), EngineError> {
+        // nothing changes if it fails, so a bad trade can't leave a hand half updated
+        let amount = match card {
+            Card::Spade => &mut self.spades,
+            Card::Club => &mut self.clubs,
+            Card::Diamond => &mut self.diamonds,
+            Card::Heart => &mut self.hearts,
+        };
+        *amount = if add {
+            amount.checked_add(1).ok_or(EngineError::Overflow)?
+        } else {
+            amount.checked_sub(1).ok_or(EngineError::InsufficientInventory(card))?
+        };
+        Ok(())
     }
 
     pub fn get(&self, card: &Card) -> usize {
@@ -79,4 +61,8 @@ impl Inventory {
             Card::Heart => self.hearts,
         }
     }
+
+    pub fn total(&self) -> usize {
+        self.spades + self.clubs + self.diamonds + self.hearts
+    }
 }
\ No newline at end of file
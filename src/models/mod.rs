@@ -1,4 +1,5 @@
 use super::{PlayerName, CL};
+use serde::{Serialize, Deserialize};
 
 pub mod event;
 pub use event::*;
@@ -8,9 +9,17 @@ pub mod inventory;
 pub use inventory::*;
 pub mod order;
 pub use order::*;
+pub mod tracker;
+pub use tracker::*;
+pub mod fair_value;
+pub use fair_value::*;
+pub mod snapshot;
+pub use snapshot::*;
+pub mod error;
+pub use error::*;
 
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Card {
     Spade,
     Club,
@@ -28,6 +37,15 @@ impl Card {
         }
     }
 
+    pub fn plural(&self) -> &str {
+        match self {
+            Card::Spade => "Spades",
+            Card::Club => "Clubs",
+            Card::Diamond => "Diamonds",
+            Card::Heart => "Hearts",
+        }
+    }
+
     pub fn get_goal_suit(&self) -> Card {
         match self {
             Card::Spade => Card::Club,
@@ -1,16 +1,57 @@
 use super::{Card, PlayerName};
+use serde::{Serialize, Deserialize};
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Direction {
     Buy,
     Sell,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
     pub player_name: PlayerName,
     pub price: usize,
     pub direction: Direction,
     pub card: Card,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cancel {
+    pub player_name: PlayerName,
+    pub direction: Direction,
+    pub card: Card,
+}
+
+// stamped by the match maker the moment it takes a request off the queue
+// - seq only ever goes up, so it's the order requests were received in across the whole game
+// - timestamp is microseconds since the match maker started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stamp {
+    pub seq: u64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sequenced {
+    pub stamp: Stamp,
+    pub request: Request,
+}
+
+// everything a player can send to the match maker
+#[derive(Debug, Clone)]
+pub enum Request {
+    Order(Order),
+    Cancel(Cancel),
+    Disconnect(PlayerName), // the seat's bot is gone, sent for it by whoever was running it
+}
+
+impl Request {
+    pub fn player_name(&self) -> &PlayerName {
+        match self {
+            Request::Order(order) => &order.player_name,
+            Request::Cancel(cancel) => &cancel.player_name,
+            Request::Disconnect(player_name) => player_name,
+        }
+    }
+}
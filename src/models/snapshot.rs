@@ -0,0 +1,70 @@
+use super::{Card, Book, Inventory, Trade, PlayerName};
+use super::super::match_maker::Metrics;
+use std::collections::HashMap;
+use tokio::time::{Duration, Instant};
+
+
+// =-= Snapshot =-= //
+// - the whole table as the match maker sees it, republished after every request it processes
+// - anything that wants to watch the game (dashboard, etc.) reads this instead of scraping the console
+// - only the end of the tape is kept, so publishing costs the same on the last trade of a busy round as on the first
+
+pub const SNAPSHOT_TAPE: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub round: u32,
+    pub trading: bool,
+    pub round_start: Option<Instant>,
+    pub round_duration: Duration,
+    pub player_names: Vec<PlayerName>,
+    pub books: HashMap<Card, Book>,
+    pub points: HashMap<PlayerName, usize>,
+    pub inventories: HashMap<PlayerName, Inventory>,
+    pub trades: Vec<Trade>, // the last `SNAPSHOT_TAPE` trades of this round's tape
+    pub trade_count: usize, // every trade this round, not just the ones kept
+    pub orders: usize, // requests processed this round
+    pub queue: usize,
+    pub common_suit: Option<Card>, // only revealed once the round is over
+    pub goal_suit: Option<Card>,
+    pub point_changes: HashMap<PlayerName, i64>, // from the last settled round
+    pub throttled: HashMap<PlayerName, usize>, // requests rejected or held back this round
+    pub metrics: Metrics, // see `match_maker/metrics.rs`
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self {
+            round: 0,
+            trading: false,
+            round_start: None,
+            round_duration: Duration::from_secs(60 * 4),
+            player_names: Vec::new(),
+            books: HashMap::new(),
+            points: HashMap::new(),
+            inventories: HashMap::new(),
+            trades: Vec::new(),
+            trade_count: 0,
+            orders: 0,
+            queue: 0,
+            common_suit: None,
+            goal_suit: None,
+            point_changes: HashMap::new(),
+            throttled: HashMap::new(),
+            metrics: Metrics::new(),
+        }
+    }
+
+    pub fn seconds_left(&self) -> u64 {
+        match self.round_start {
+            Some(start) if self.trading => self.round_duration.saturating_sub(start.elapsed()).as_secs(),
+            _ => 0,
+        }
+    }
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,137 @@
+use super::{Card, Inventory, PlayerName, Trade};
+use std::collections::HashMap;
+use std::fmt;
+
+
+// =-= CardTracker =-= //
+// - reconstructs what can be *proven* about every hand from our own deal and the public trade tape
+// - buying a card is public, so the buyer is known to hold at least one more of that suit
+// - selling a card the tape can't account for means it came from the seller's hidden hand,
+// which tells us the seller was dealt at least that many of the suit (e.g. "Seller held at least 3 Hearts")
+// - everything is a lower bound, the only upper bound we get is the hand size (dealt + bought - sold)
+
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    pub player_name: PlayerName,
+    pub card: Card,
+    pub at_least: usize,
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} held at least {} {}", self.player_name, self.at_least, self.card.plural())
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct CardTracker {
+    pub name: PlayerName,
+    pub known: HashMap<PlayerName, Inventory>, // minimum each player holds right now
+    pub dealt: HashMap<PlayerName, Inventory>, // minimum each player was dealt, revealed by their sales
+    pub hand_sizes: HashMap<PlayerName, usize>,
+}
+
+impl CardTracker {
+    pub fn new(name: PlayerName) -> Self {
+        Self {
+            name,
+            known: HashMap::new(),
+            dealt: HashMap::new(),
+            hand_sizes: HashMap::new(),
+        }
+    }
+
+    pub fn deal(&mut self, hand: Inventory, player_names: Vec<PlayerName>) {
+        // everyone is dealt the same amount of cards, so our own hand tells us the size of theirs
+        self.known.clear();
+        self.dealt.clear();
+        self.hand_sizes.clear();
+
+        for player_name in player_names {
+            let starting = if player_name == self.name { hand } else { Inventory::new() };
+            self.known.insert(player_name.clone(), starting);
+            self.dealt.insert(player_name.clone(), starting);
+            self.hand_sizes.insert(player_name, hand.total());
+        }
+    }
+
+    pub fn record_trade(&mut self, trade: &Trade) {
+        if let Some(known) = self.known.get_mut(&trade.seller) {
+            let held = known.get(&trade.card);
+            if held == 0 {
+                // the tape can't account for this card, so it must've been dealt to them
+                let dealt = self.dealt.entry(trade.seller.clone()).or_default();
+                let _ = dealt.change(trade.card.clone(), true);
+            } else {
+                let _ = known.change(trade.card.clone(), false); // can't fail, they hold at least one
+            }
+        }
+        if let Some(size) = self.hand_sizes.get_mut(&trade.seller) {
+            *size = size.saturating_sub(1);
+        }
+
+        let _ = self.known.entry(trade.buyer.clone()).or_default().change(trade.card.clone(), true);
+        *self.hand_sizes.entry(trade.buyer.clone()).or_default() += 1;
+    }
+
+    pub fn min_holding(&self, player_name: &PlayerName, card: &Card) -> usize {
+        self.known.get(player_name).map(|inventory| inventory.get(card)).unwrap_or(0)
+    }
+
+    pub fn unknown_cards(&self, player_name: &PlayerName) -> usize {
+        let hand_size = self.hand_sizes.get(player_name).copied().unwrap_or(0);
+        let known = self.known.get(player_name).map(|inventory| inventory.total()).unwrap_or(0);
+        hand_size.saturating_sub(known)
+    }
+
+    pub fn max_holding(&self, player_name: &PlayerName, card: &Card) -> usize {
+        self.min_holding(player_name, card) + self.unknown_cards(player_name)
+    }
+
+    pub fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        for (player_name, dealt) in &self.dealt {
+            if *player_name == self.name {
+                continue;
+            }
+            for card in [Card::Spade, Card::Club, Card::Diamond, Card::Heart] {
+                let at_least = dealt.get(&card);
+                if at_least > 0 {
+                    constraints.push(Constraint { player_name: player_name.clone(), card, at_least });
+                }
+            }
+        }
+        constraints
+    }
+
+    pub fn majority_leader(&self, card: &Card) -> Option<(PlayerName, usize)> {
+        // who is *proven* to hold the most of this suit, ties go to nobody
+        let mut leader: Option<(PlayerName, usize)> = None;
+        let mut tied = false;
+        for (player_name, inventory) in &self.known {
+            let held = inventory.get(card);
+            match &leader {
+                Some((_, most)) if held < *most => {},
+                Some((_, most)) if held == *most => tied = true,
+                _ => {
+                    leader = Some((player_name.clone(), held));
+                    tied = false;
+                }
+            }
+        }
+
+        match leader {
+            Some((_, 0)) => None,
+            Some(_) if tied => None,
+            leader => leader,
+        }
+    }
+
+    pub fn rival_could_exceed(&self, player_name: &PlayerName, card: &Card, count: usize) -> bool {
+        // can anyone else possibly be holding more than `count` of this suit
+        self.known.keys()
+            .filter(|other| *other != player_name)
+            .any(|other| self.max_holding(other, card) > count)
+    }
+}
@@ -1,35 +1,37 @@
-use super::{Card, Direction, Book, Trade, Inventory, Order, Event, CL, PlayerName};
+use super::{Card, Direction, Book, Trade, Inventory, Order, Request, Event, AckStatus, CardTracker, PlayerName, PickOffParams};
 use kanal::AsyncSender;
 use tokio::sync::broadcast::Sender;
 use std::sync::Arc;
+use tracing::{debug, error};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
-use std::time::Instant;
+use tokio::time::Instant;
 
 pub struct EventDrivenPlayer {
     pub name: PlayerName,
     pub timer: Instant,
-    pub verbose: bool,
     pub inventory: Inventory,
     pub trades: Vec<Trade>,
+    pub tracker: CardTracker,
+    pub pick_off: PickOffParams,
     pub event_receiver: Sender<Event>,
-    pub order_sender: Arc<AsyncSender<Order>>,
+    pub order_sender: Arc<AsyncSender<Request>>,
     pub trading: Arc<AtomicBool>,
 }
 
 impl EventDrivenPlayer {
     pub fn new(
         player_name: PlayerName,
-        verbose: bool,
         event_receiver: Sender<Event>,
-        order_sender: Arc<AsyncSender<Order>>,
+        order_sender: Arc<AsyncSender<Request>>,
     ) -> Self {
         Self {
-            name: player_name,
+            name: player_name.clone(),
             timer: Instant::now(),
-            verbose,
             inventory: Inventory::new(),
             trades: Vec::new(),
+            tracker: CardTracker::new(player_name),
+            pick_off: PickOffParams::default(),
             event_receiver,
             order_sender,
             trading: Arc::new(AtomicBool::new(false)),
@@ -54,14 +56,20 @@ impl EventDrivenPlayer {
 
                         if let Some(trade) = update.trade { 
                             self.trades.push(trade.clone()); // push trade for historical reasons (if we want to analyze) & update inventory
-                            if trade.buyer == self.name {
-                                self.inventory.change(trade.card, true);
+                            self.tracker.record_trade(&trade);
+                            let change = if trade.buyer == self.name {
+                                self.inventory.change(trade.card, true)
                             } else if trade.seller == self.name {
-                                self.inventory.change(trade.card, false);
+                                self.inventory.change(trade.card, false)
+                            } else {
+                                Ok(())
+                            };
+                            if let Err(e) = change {
+                                error!(player = %self.name, error = %e, "inventory out of sync with the tape");
                             }
                         }
 
-                        let seconds_left = 240 - self.timer.elapsed().as_secs();
+                        let seconds_left = 240u64.saturating_sub(self.timer.elapsed().as_secs()); // the round can run a little past 240s before EndRound lands
 
                         let inventory = self.inventory;
 
@@ -70,7 +78,7 @@ impl EventDrivenPlayer {
                         let diamonds_book = update.diamonds;
                         let hearts_book = update.hearts;
 
-                        //println!("{}{:?} | Inventory |:| Spades: {} | Clubs: {} | Diamonds: {} | Hearts: {}{}", CL::Dull.get(), self.name, inventory.spades, inventory.clubs, inventory.diamonds, inventory.hearts, CL::End.get());
+                        //console!("{}{:?} | Inventory |:| Spades: {} | Clubs: {} | Diamonds: {} | Hearts: {}{}", CL::Dull.get(), self.name, inventory.spades, inventory.clubs, inventory.diamonds, inventory.hearts, CL::End.get());
 
                         // be careful with EventDriven, this can lead to a snowball of events if the # of orders leads from 1 -> many
                         // core logic goes here (example below)
@@ -78,33 +86,36 @@ impl EventDrivenPlayer {
 
                         
                         
-                        match self.name {
-                            PlayerName::PickOff => {
-                                self.pick_off(seconds_left, inventory.spades, spades_book, Card::Spade).await;
-                                self.pick_off(seconds_left, inventory.clubs, clubs_book, Card::Club).await;
-                                self.pick_off(seconds_left, inventory.diamonds, diamonds_book, Card::Diamond).await;
-                                self.pick_off(seconds_left, inventory.hearts, hearts_book, Card::Heart).await;
-                            },
-                            _ => {}
+                        if self.name == PlayerName::PickOff {
+                            self.pick_off(seconds_left, inventory.spades, spades_book, Card::Spade).await;
+                            self.pick_off(seconds_left, inventory.clubs, clubs_book, Card::Club).await;
+                            self.pick_off(seconds_left, inventory.diamonds, diamonds_book, Card::Diamond).await;
+                            self.pick_off(seconds_left, inventory.hearts, hearts_book, Card::Heart).await;
                         }
 
                     }
                     Event::DealCards(players_inventory) => {
-                        self.inventory = players_inventory.get(&self.name).unwrap().clone();
+                        self.inventory = *players_inventory.get(&self.name).unwrap();
+                        self.tracker.deal(self.inventory, players_inventory.keys().cloned().collect());
                         
-                        if self.verbose {
-                            println!("{}[+] {:?} |:| Received cards: {:?}{}", CL::DullGreen.get(), self.name, self.inventory, CL::End.get());
-                        }
+                        debug!(player = %self.name, hand = ?self.inventory, "received cards");
                         
                         self.trading.store(true, Ordering::Release);
                         self.timer = Instant::now();
                     },
                     Event::EndRound => {
                         self.trading.store(false, Ordering::Release);
+                    },
+                    Event::Ack(ack) => {
+                        if let AckStatus::Rejected(reason) = ack.status {
+                            if ack.player_name == self.name {
+                                debug!(player = %self.name, seq = ack.seq, suit = ?ack.card, reason = ?reason, "order rejected");
+                            }
+                        }
                     }
                 }
             } else {
-                println!("{}[!] {:?} |:| Event receiver dropped{}", CL::Red.get(), self.name, CL::End.get());
+                error!(player = %self.name, "event receiver dropped");
             }
         }
     }
@@ -135,44 +146,32 @@ impl EventDrivenPlayer {
                 card: card.clone(),
             };
     
-            if self.verbose {
-                println!("{:?} |:| Sending order: {:?}", self.name, order);
-            }
+            debug!(player = %self.name, suit = ?order.card, direction = ?order.direction, price = order.price, "sending order");
     
-            if let Err(e) = self.order_sender.send(order).await {
-                println!("[!] {:?} |:| Error sending order: {:?}", self.name, e);
+            if let Err(e) = self.order_sender.send(Request::Order(order)).await {
+                error!(player = %self.name, error = ?e, "unable to send order");
             }
         }
         
     }
 
     pub fn get_max_price_from_seconds(&self, seconds_left: u64) -> (usize, usize) {
-        if seconds_left < 20 {
-            (0, 0)
-        } else if seconds_left < 40 {
-            (2, 3)
-        } else if seconds_left < 60 {
-            (3, 4)
-        } else if seconds_left < 120 {
-            (4, 6)
-        } else {
-            (5, 8)
-        }
+        // the buckets are in `PickOffParams`: (0, 0) under 20s, (2, 3) under 40s, (3, 4) under 60s, (4, 6) under 120s, (5, 8) before that
+        let bucket = self.pick_off.bucket(seconds_left);
+        (bucket.open, bucket.close)
     }
 
     pub async fn pick_off(&self, seconds_left: u64, inventory: usize, book: Book, card: Card) {
         let (open_price, close_price) = self.get_max_price_from_seconds(seconds_left);
-        if inventory <= 2 {
-            if book.ask.price < open_price {
-                self.send_order(book.ask.price, Direction::Buy, &card, &book).await;
-            }
+        if inventory <= self.pick_off.max_inventory && book.ask.price < open_price {
+            self.send_order(book.ask.price, Direction::Buy, &card, &book).await;
         }
 
         if inventory > 0 {
             if book.bid.price >= close_price {
                 self.send_order(book.bid.price, Direction::Sell, &card, &book).await;
             }
-            if book.ask.price > 5 {
+            if book.ask.price > self.pick_off.ask_floor {
                 self.send_order(book.ask.price - 1, Direction::Sell, &card, &book).await;
             }
         }
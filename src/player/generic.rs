@@ -1,20 +1,21 @@
-use super::{Card, Direction, Book, Trade, Inventory, Order, Event, CL, PlayerName};
+use super::{Card, Direction, Book, Trade, Inventory, Order, Request, Event, AckStatus, CardTracker, PlayerName, SellerParams};
 use kanal::{AsyncSender};
 use tokio::sync::broadcast::{Sender, Receiver};
+use tokio::sync::broadcast::error::RecvError;
 use std::sync::Arc;
+use tracing::{debug, warn, error};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use tokio::sync::Mutex;
 use rand::Rng;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
-use std::time::Instant;
+use tokio::time::Instant;
 
 
 
 pub struct GenericPlayer {
     pub name: PlayerName,
-    pub verbose: bool,
     pub timer: Arc<Mutex<Instant>>,
     pub spades_book: Arc<Mutex<Book>>,
     pub clubs_book: Arc<Mutex<Book>>,
@@ -22,34 +23,36 @@ pub struct GenericPlayer {
     pub hearts_book: Arc<Mutex<Book>>,
     pub inventory: Arc<Mutex<Inventory>>,
     pub trades: Arc<Mutex<Vec<Trade>>>,
+    pub tracker: Arc<Mutex<CardTracker>>,
     pub lower_frequency: u64,
     pub higher_frequency: u64,
+    pub seller: SellerParams, // only Seller plays off these so far
     pub event_receiver: Sender<Event>,
-    pub order_sender: Arc<AsyncSender<Order>>,
+    pub order_sender: Arc<AsyncSender<Request>>,
     pub trading: Arc<AtomicBool>,
 }
 
 impl GenericPlayer {
     pub fn new(
         player_name: PlayerName,
-        verbose: bool,
         lower_frequency: u64,
         higher_frequency: u64,
         event_receiver: Sender<Event>,
-        order_sender: Arc<AsyncSender<Order>>,
+        order_sender: Arc<AsyncSender<Request>>,
     ) -> Self {
         Self {
-            name: player_name,
-            verbose,
-            timer: Arc::new(Mutex::new(std::time::Instant::now())),
+            name: player_name.clone(),
+            timer: Arc::new(Mutex::new(Instant::now())),
             spades_book: Arc::new(Mutex::new(Book::new())),
             clubs_book: Arc::new(Mutex::new(Book::new())),
             diamonds_book: Arc::new(Mutex::new(Book::new())),
             hearts_book: Arc::new(Mutex::new(Book::new())),
             inventory: Arc::new(Mutex::new(Inventory::new())),
             trades: Arc::new(Mutex::new(Vec::new())),
+            tracker: Arc::new(Mutex::new(CardTracker::new(player_name))),
             lower_frequency,
             higher_frequency,
+            seller: SellerParams::default(),
             event_receiver,
             order_sender,
             trading: Arc::new(AtomicBool::new(false)),
@@ -70,16 +73,16 @@ impl GenericPlayer {
                 continue;
             }
 
-            let seconds_left = 240 - self.timer.lock().await.elapsed().as_secs();
+            let seconds_left = 240u64.saturating_sub(self.timer.lock().await.elapsed().as_secs()); // the round can run a little past 240s before EndRound lands
 
-            let inventory = self.inventory.lock().await.clone();
+            let inventory = *self.inventory.lock().await;
 
             let spades_book = self.spades_book.lock().await.clone();
             let clubs_book = self.clubs_book.lock().await.clone();
             let diamonds_book = self.diamonds_book.lock().await.clone();
             let hearts_book = self.hearts_book.lock().await.clone();
 
-            println!("{}{:?} | Inventory |:| Spades: {} | Clubs: {} | Diamonds: {} | Hearts: {}{}", CL::Dull.get(), self.name, inventory.spades, inventory.clubs, inventory.diamonds, inventory.hearts, CL::End.get());
+            debug!(player = %self.name, spades = inventory.spades, clubs = inventory.clubs, diamonds = inventory.diamonds, hearts = inventory.hearts, "inventory");
 
             // with the above information, we can now decide what to do
             // core logic goes here (examples below)
@@ -101,14 +104,14 @@ impl GenericPlayer {
                     self.praying_mantis_sell(seconds_left, inventory.diamonds, diamonds_book.clone(), Card::Diamond).await;
                     self.praying_mantis_sell(seconds_left, inventory.hearts, hearts_book.clone(), Card::Heart).await;
 
-                    let mut cards = vec![
+                    let mut cards = [
                         (Card::Spade, spades_book.last_trade.unwrap_or(0)),
                         (Card::Club, clubs_book.last_trade.unwrap_or(0)),
                         (Card::Diamond, diamonds_book.last_trade.unwrap_or(0)),
                         (Card::Heart, hearts_book.last_trade.unwrap_or(0)),
                     ];
 
-                    cards.sort_by(|a, b| b.1.cmp(&a.1));
+                    cards.sort_by_key(|card| std::cmp::Reverse(card.1));
 
                     let most_expensive_card = cards[0].0.clone();
                     let (inventory, book) = match most_expensive_card {
@@ -174,12 +177,10 @@ impl GenericPlayer {
                 card: card.clone(),
             };
     
-            if self.verbose {
-                println!("{:?} |:| Sending order: {:?}", self.name, order);
-            }
+            debug!(player = %self.name, suit = ?order.card, direction = ?order.direction, price = order.price, "sending order");
     
-            if let Err(e) = self.order_sender.send(order).await {
-                println!("[!] {:?} |:| Error sending order: {:?}", self.name, e);
+            if let Err(e) = self.order_sender.send(Request::Order(order)).await {
+                error!(player = %self.name, error = ?e, "unable to send order");
             }
         }
         
@@ -199,14 +200,14 @@ impl GenericPlayer {
             true => {
                 let price = rng.gen_range(1..15);
                 if current_inventory < 4 {
-                    println!("NOISY |:| BUY | Random card: {:?} | Price: {}", random_card, price);
+                    debug!(player = %self.name, suit = ?random_card, price, "noisy buy");
                     self.send_order(price, Direction::Buy, &random_card, &book).await;
                 }
             },
             false => {
                 let price = rng.gen_range(1..15);
                 if current_inventory > 0 {
-                    println!("NOISY |:| SELL | Random card: {:?} | Price: {} | current_inventory: {}", random_card, price, current_inventory);
+                    debug!(player = %self.name, suit = ?random_card, price, held = current_inventory, "noisy sell");
                     self.send_order(price, Direction::Sell, &random_card, &book).await;
                 }
             }
@@ -218,18 +219,14 @@ impl GenericPlayer {
         // we'll wait to the last minute and buy up the inventory of the goal card. While we wait for the last minute, we'll sell off the other inventory
         // - something of note for this strategy: it assumes that the market is right and that the goal card is the most valuable card,
         // if the participants are not rational / operating at a high skill level, this strategy will simply not work
-        if seconds_left >= 60 {
-            if inventory > 0 && book.ask.price >= 7 {
-                self.send_order(book.ask.price - 1, Direction::Sell, &card, &book).await;
-            }
+        if seconds_left >= 60 && inventory > 0 && book.ask.price >= 7 {
+            self.send_order(book.ask.price - 1, Direction::Sell, &card, &book).await;
         }
     }
 
-    pub async fn praying_mantis_snipe(&self, seconds_left: u64, inventory: usize, book: Book, card: Card) {
-        if seconds_left <= 60 {
-            if book.ask.price <= 9 { // at this point in the game, we shouldn't expect to gain the most goal suits, so we'll try and pick off any < 10 to net +(10-price)
-                self.send_order(book.ask.price, Direction::Buy, &card, &book).await;
-            }
+    pub async fn praying_mantis_snipe(&self, seconds_left: u64, _inventory: usize, book: Book, card: Card) {
+        if seconds_left <= 60 && book.ask.price <= 9 { // at this point in the game, we shouldn't expect to gain the most goal suits, so we'll try and pick off any < 10 to net +(10-price)
+            self.send_order(book.ask.price, Direction::Buy, &card, &book).await;
         }
     }
 
@@ -288,32 +285,14 @@ impl GenericPlayer {
         // to follow this expectation and sell the inventory less and less - knowing the goal card will always be picked off
         // - strategy adaptation: see which inventory is picked off first, then quickly sell off the other inventory while
         // bidding back the picked off inventory (betting on the market bring right)
+        // - the phases (and their prices) are in `SellerParams`, the defaults being market 6 / limit 8 with 3+ minutes left,
+        // then 5 / 6, 4 / 6 and 3 / 4 for the last minute
         if inventory > 0 {
-            if seconds_left >= 180 {
-                // market: 6, limit: 7
-                if book.bid.price >= 6 {
-                    self.send_order(book.bid.price, Direction::Sell, &card, &book).await;
-                }
-                self.send_order(8, Direction::Sell, &card, &book).await;
-            } else if seconds_left > 120 && seconds_left < 180 {
-                // market: 5, limit: 6
-                if book.bid.price >= 5 {
-                    self.send_order(book.bid.price, Direction::Sell, &card, &book).await;
-                }
-                self.send_order(6, Direction::Sell, &card, &book).await;
-            } else if seconds_left > 60 && seconds_left < 120 {
-                // market: 4, limit: 5
-                if book.bid.price >= 4 {
-                    self.send_order(book.bid.price, Direction::Sell, &card, &book).await;
-                }
-                self.send_order(6, Direction::Sell, &card, &book).await;
-            } else {
-                // market: 3, limit: 4
-                if book.bid.price >= 3 {
-                    self.send_order(book.bid.price, Direction::Sell, &card, &book).await;
-                }
-                self.send_order(4, Direction::Sell, &card, &book).await;
+            let phase = self.seller.phase(seconds_left);
+            if book.bid.price >= phase.market {
+                self.send_order(book.bid.price, Direction::Sell, &card, &book).await;
             }
+            self.send_order(phase.limit, Direction::Sell, &card, &book).await;
         }
     }
 
@@ -376,27 +355,40 @@ impl GenericPlayer {
 
         let inventory: Arc<Mutex<Inventory>> = self.inventory.clone();
         let trades: Arc<Mutex<Vec<Trade>>> = self.trades.clone();
+        let tracker: Arc<Mutex<CardTracker>> = self.tracker.clone();
         let trading: Arc<AtomicBool> = self.trading.clone();
 
         let name: PlayerName = self.name.clone();
-        let verbose: bool = self.verbose;
         let timer = self.timer.clone();
         
         tokio::task::spawn(async move {
             loop {
 
-                let event = event_receiver.recv().await.unwrap();
+                let event = match event_receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => { // a quiet console lets the match maker outrun us, the next update has the latest books
+                        warn!(player = %name, skipped, "lagged behind the match maker");
+                        continue;
+                    },
+                    Err(RecvError::Closed) => break,
+                };
                 match event {
                     Event::Update(update) => {
                         if let Some(trade) = update.trade { // push trade for historical reasons (if we want to analyze) & update inventory
                             let mut trade_lock = trades.lock().await;
                             trade_lock.push(trade.clone());
+                            tracker.lock().await.record_trade(&trade);
 
                             let mut inventory_lock = inventory.lock().await;
-                            if trade.buyer == name {
-                                inventory_lock.change(trade.card, true);
+                            let change = if trade.buyer == name {
+                                inventory_lock.change(trade.card, true)
                             } else if trade.seller == name {
-                                inventory_lock.change(trade.card, false);
+                                inventory_lock.change(trade.card, false)
+                            } else {
+                                Ok(())
+                            };
+                            if let Err(e) = change {
+                                error!(player = %name, error = %e, "inventory out of sync with the tape");
                             }
                         }
 
@@ -416,11 +408,10 @@ impl GenericPlayer {
                     }
                     Event::DealCards(players_inventory) => {
                         let mut inventory_lock = inventory.lock().await;
-                        *inventory_lock = players_inventory.get(&name).unwrap().clone();
+                        *inventory_lock = *players_inventory.get(&name).unwrap();
+                        tracker.lock().await.deal(*inventory_lock, players_inventory.keys().cloned().collect());
                         
-                        if verbose {
-                            println!("{}[+] {:?} |:| Received cards: {:?}{}", CL::DullGreen.get(), name, inventory_lock, CL::End.get());
-                        }
+                        debug!(player = %name, hand = ?inventory_lock, "received cards");
                         
                         trading.store(true, Ordering::Release);
                         let mut timer_lock = timer.lock().await;
@@ -428,6 +419,13 @@ impl GenericPlayer {
                     },
                     Event::EndRound => {
                         trading.store(false, Ordering::Release);
+                    },
+                    Event::Ack(ack) => {
+                        if let AckStatus::Rejected(reason) = ack.status {
+                            if ack.player_name == name {
+                                debug!(player = %name, seq = ack.seq, suit = ?ack.card, reason = ?reason, "order rejected");
+                            }
+                        }
                     }
                 }
 
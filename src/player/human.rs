@@ -0,0 +1,253 @@
+use super::{Card, Direction, Book, Trade, Inventory, Order, Cancel, Request, Event, AckStatus, CardTracker, CL, PlayerName};
+use crate::tui::{Screen, book_line, spawn_key_reader, request_quit};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kanal::AsyncSender;
+use tokio::sync::broadcast::Sender;
+use tracing::error;
+use tokio::sync::broadcast::error::RecvError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+
+// =-= HumanPlayer =-= //
+// - a seat for us, rendered in the terminal and driven by the keyboard
+// - orders go through the exact same channel as the bots, so the match maker can't tell the difference
+// - keys: up/down picks the suit, digits type a price, b buys, s sells, c pulls our quotes, esc clears, q leaves
+
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+pub struct HumanPlayer {
+    pub name: PlayerName,
+    pub timer: Instant,
+    pub trading: bool,
+    pub inventory: Inventory,
+    pub books: HashMap<Card, Book>,
+    pub trades: Vec<Trade>,
+    pub tracker: CardTracker,
+    pub selected: usize,
+    pub price: String,
+    pub status: String,
+    pub event_receiver: Sender<Event>,
+    pub order_sender: Arc<AsyncSender<Request>>,
+}
+
+impl HumanPlayer {
+    pub fn new(
+        player_name: PlayerName,
+        event_receiver: Sender<Event>,
+        order_sender: Arc<AsyncSender<Request>>,
+    ) -> Self {
+        let mut books = HashMap::new();
+        for card in SUITS {
+            books.insert(card, Book::new());
+        }
+
+        Self {
+            name: player_name.clone(),
+            timer: Instant::now(),
+            trading: false,
+            inventory: Inventory::new(),
+            books,
+            trades: Vec::new(),
+            tracker: CardTracker::new(player_name),
+            selected: 0,
+            price: String::new(),
+            status: String::from("Waiting for the deal..."),
+            event_receiver,
+            order_sender,
+        }
+    }
+
+
+
+    pub async fn start(&mut self) {
+        let mut event_receiver = self.event_receiver.subscribe();
+
+        let mut key_receiver = spawn_key_reader();
+
+        let mut screen = match Screen::enter() {
+            Ok(screen) => screen,
+            Err(e) => {
+                error!(player = %self.name, error = %e, "unable to open the terminal UI");
+                return;
+            }
+        };
+
+        let mut refresh = tokio::time::interval(tokio::time::Duration::from_millis(250)); // keeps the countdown ticking
+        loop {
+            tokio::select! {
+                event = event_receiver.recv() => match event {
+                    Ok(event) => self.handle_event(event),
+                    Err(RecvError::Lagged(skipped)) => self.status = format!("Missed {} updates", skipped),
+                    Err(RecvError::Closed) => break,
+                },
+                Some(key) = key_receiver.recv() => {
+                    if !self.handle_key(key).await {
+                        break;
+                    }
+                },
+                _ = refresh.tick() => {},
+            }
+
+            if screen.draw(&self.render()).is_err() {
+                break;
+            }
+        }
+
+        // leaving the seat leaves the game, there's no one to play our hand
+        drop(screen);
+        request_quit();
+    }
+
+
+
+    pub fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Update(update) => {
+                if let Some(trade) = update.trade {
+                    self.tracker.record_trade(&trade);
+                    if trade.buyer == self.name {
+                        self.status = match self.inventory.change(trade.card.clone(), true) {
+                            Ok(()) => format!("Bought {:?} @ {} from {:?}", trade.card, trade.price, trade.seller),
+                            Err(e) => format!("Inventory out of sync with the tape: {}", e),
+                        };
+                    } else if trade.seller == self.name {
+                        self.status = match self.inventory.change(trade.card.clone(), false) {
+                            Ok(()) => format!("Sold {:?} @ {} to {:?}", trade.card, trade.price, trade.buyer),
+                            Err(e) => format!("Inventory out of sync with the tape: {}", e),
+                        };
+                    }
+                    self.trades.push(trade);
+                }
+
+                self.books.insert(Card::Spade, update.spades);
+                self.books.insert(Card::Club, update.clubs);
+                self.books.insert(Card::Diamond, update.diamonds);
+                self.books.insert(Card::Heart, update.hearts);
+            },
+            Event::DealCards(players_inventory) => {
+                self.inventory = players_inventory.get(&self.name).copied().unwrap_or_default();
+                self.tracker.deal(self.inventory, players_inventory.keys().cloned().collect());
+                self.trades.clear();
+                self.trading = true;
+                self.timer = Instant::now();
+                self.status = String::from("Cards dealt, good luck!");
+            },
+            Event::EndRound => {
+                self.trading = false;
+                self.status = String::from("Round over, waiting for the next deal...");
+            },
+            Event::Ack(ack) if ack.player_name == self.name => match ack.status {
+                AckStatus::Rejected(reason) => self.status = format!("Rejected {:?} {:?}: {:?}", ack.direction, ack.card, reason),
+                AckStatus::Dropped => self.status = format!("Dropped {:?} {:?}, the books were reset by a trade", ack.direction, ack.card),
+                AckStatus::NotImproved => self.status = format!("{:?} {:?} @ {} didn't beat the book", ack.direction, ack.card, ack.price.unwrap_or_default()),
+                _ => {},
+            },
+            Event::Ack(_) => {},
+        }
+    }
+
+    pub async fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let card = SUITS[self.selected].clone();
+        match key.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Up => self.selected = (self.selected + 3) % 4,
+            KeyCode::Down => self.selected = (self.selected + 1) % 4,
+            KeyCode::Char(digit) if digit.is_ascii_digit() && self.price.len() < 2 => self.price.push(digit),
+            KeyCode::Backspace => {
+                self.price.pop();
+            },
+            KeyCode::Esc => self.price.clear(),
+            KeyCode::Char('b') => {
+                // no price typed means lift the ask, if there is one (an empty side only holds the 99 placeholder)
+                let ask = &self.books[&card].ask;
+                let price = match self.price.parse::<usize>() {
+                    Ok(price) => price,
+                    Err(_) if ask.player_name != PlayerName::None => ask.price,
+                    Err(_) => {
+                        self.status = format!("No ask on {} to lift, type a price first", card.plural());
+                        return true;
+                    },
+                };
+                self.send(Request::Order(Order { player_name: self.name.clone(), price, direction: Direction::Buy, card })).await;
+                self.price.clear();
+            },
+            KeyCode::Char('s') => {
+                // no price typed means hit the bid, if there is one (an empty side only holds the 0 placeholder)
+                let bid = &self.books[&card].bid;
+                let price = match self.price.parse::<usize>() {
+                    Ok(price) => price,
+                    Err(_) if bid.player_name != PlayerName::None => bid.price,
+                    Err(_) => {
+                        self.status = format!("No bid on {} to hit, type a price first", card.plural());
+                        return true;
+                    },
+                };
+                self.send(Request::Order(Order { player_name: self.name.clone(), price, direction: Direction::Sell, card })).await;
+                self.price.clear();
+            },
+            KeyCode::Char('c') => {
+                self.send(Request::Cancel(Cancel { player_name: self.name.clone(), direction: Direction::Buy, card: card.clone() })).await;
+                self.send(Request::Cancel(Cancel { player_name: self.name.clone(), direction: Direction::Sell, card })).await;
+            },
+            _ => {},
+        }
+        true
+    }
+
+    pub async fn send(&mut self, request: Request) {
+        if !self.trading {
+            self.status = String::from("No round in progress");
+            return;
+        }
+
+        self.status = match &request {
+            Request::Order(order) => format!("Sent {:?} {:?} @ {}", order.direction, order.card, order.price),
+            Request::Cancel(cancel) => format!("Cancelled quotes on {:?}", cancel.card),
+            Request::Disconnect(_) => String::from("Left the table"),
+        };
+        if let Err(e) = self.order_sender.send(request).await {
+            self.status = format!("Error sending order: {:?}", e);
+        }
+    }
+
+
+
+    pub fn render(&self) -> Vec<String> {
+        let seconds_left = 240u64.saturating_sub(self.timer.elapsed().as_secs());
+        let clock = if self.trading { format!("{}:{:02}", seconds_left / 60, seconds_left % 60) } else { String::from("-:--") };
+
+        let mut lines = Vec::new();
+        lines.push(format!("{}|=============== Figgie Auto |:| {:?} ===============|{}  Time left: {}{}", CL::DimLightBlue.get(), self.name, CL::Dull.get(), CL::Teal.get(), clock));
+        lines.push(String::new());
+
+        for (idx, card) in SUITS.iter().enumerate() {
+            let book = &self.books[card];
+            let (marker, color) = if idx == self.selected { ("> ", CL::LimeGreen) } else { ("  ", CL::DullTeal) };
+            let held = self.inventory.get(card);
+            lines.push(format!("{}{}{} |:| Hand: {}{}x", color.get(), marker, book_line(card, book, &color), CL::Teal.get(), held));
+        }
+        lines.push(String::new());
+
+        let price = if self.price.is_empty() { String::from("market") } else { self.price.clone() };
+        lines.push(format!("{}Order |:| {}{}{} @ {}{}", CL::Dull.get(), CL::LimeGreen.get(), SUITS[self.selected].plural(), CL::Dull.get(), CL::Teal.get(), price));
+        lines.push(format!("{}Keys  |:| up/down suit | 0-9 price | b buy | s sell | c cancel | esc clear | q quit", CL::Dull.get()));
+        lines.push(format!("{}>> {}", CL::Orange.get(), self.status));
+        lines.push(String::new());
+
+        lines.push(format!("{}=---= Trade Tape =---={}", CL::DimLightBlue.get(), CL::End.get()));
+        for trade in self.trades.iter().rev().take(10) {
+            lines.push(format!("{} - {:?} @ {} | {:?} -> {:?}", CL::Dull.get(), trade.card, trade.price, trade.seller, trade.buyer));
+        }
+        lines.push(String::new());
+
+        lines.push(format!("{}=---= Tape Reveals =---={}", CL::DimLightBlue.get(), CL::End.get()));
+        for constraint in self.tracker.constraints() {
+            lines.push(format!("{} - {}", CL::Dull.get(), constraint));
+        }
+
+        lines
+    }
+}
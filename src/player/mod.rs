@@ -1,4 +1,9 @@
-use super::{Card, Direction, Book, Trade, Inventory, Order, Event, CL};
+use super::{Card, Direction, Book, Trade, Inventory, Order, Cancel, Request, Event, AckStatus, CardTracker, CL};
+use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast::Sender;
+use tokio::task::JoinHandle;
+use kanal::AsyncSender;
+use std::sync::Arc;
 
 pub mod event_driven;
 pub use event_driven::*;
@@ -9,7 +14,14 @@ pub use generic::GenericPlayer;
 pub mod tilt;
 pub use tilt::TiltInventory;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub mod human;
+pub use human::HumanPlayer;
+
+pub mod params;
+pub use params::*;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum PlayerName {
     Spread,
     Seller,
@@ -20,8 +32,100 @@ pub enum PlayerName {
     TiltInventory,
     TheHoarder,
     PrayingMantis,
+    Human,
+    External(String), // a bot living outside of this process, see `gateway`
     None,
 }
 
+impl From<String> for PlayerName {
+    fn from(name: String) -> Self {
+        // anything that isn't one of ours is someone else's bot
+        match name.as_str() {
+            "Spread" => PlayerName::Spread,
+            "Seller" => PlayerName::Seller,
+            "Taker" => PlayerName::Taker,
+            "Noisy" => PlayerName::Noisy,
+            "WildestDreams" => PlayerName::WildestDreams,
+            "PickOff" => PlayerName::PickOff,
+            "TiltInventory" => PlayerName::TiltInventory,
+            "TheHoarder" => PlayerName::TheHoarder,
+            "PrayingMantis" => PlayerName::PrayingMantis,
+            "Human" => PlayerName::Human,
+            "None" => PlayerName::None,
+            _ => PlayerName::External(name),
+        }
+    }
+}
+
+impl PlayerName {
+    pub fn is_reserved(&self) -> bool {
+        // `None` is whoever is on an empty side of the book, a seat by that name would be taken for nobody
+        matches!(self, PlayerName::None)
+    }
+}
+
+impl From<PlayerName> for String {
+    fn from(player_name: PlayerName) -> Self {
+        match player_name {
+            PlayerName::External(name) => name,
+            player_name => format!("{:?}", player_name),
+        }
+    }
+}
 
+impl std::fmt::Display for PlayerName {
+    // how a seat shows up in log fields, external bots by the name they logged in with
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", String::from(self.clone()))
+    }
+}
 
+
+pub fn spawn_player(player_name: PlayerName, params: Option<StrategyParams>, event_sender: Sender<Event>, order_sender: Arc<AsyncSender<Request>>) -> Option<JoinHandle<()>> {
+    // every built-in seat with the frequencies it's tuned for, external seats are driven by the gateway instead
+    // - `params` swaps out a strategy's defaults (see `params.rs`), a set meant for another strategy is ignored
+    let handle = match player_name {
+        PlayerName::External(_) | PlayerName::None => return None,
+        PlayerName::TiltInventory => tokio::task::spawn(async move {
+            let params = match params {
+                Some(StrategyParams::TiltInventory(params)) => params,
+                _ => TiltParams::default(),
+            };
+            let mut player: TiltInventory = TiltInventory::new(player_name, params.lower_frequency, params.higher_frequency, event_sender, order_sender);
+            player.params = params;
+            player.start().await;
+        }),
+        PlayerName::PickOff => tokio::task::spawn(async move {
+            let mut player: EventDrivenPlayer = EventDrivenPlayer::new(player_name, event_sender, order_sender);
+            if let Some(StrategyParams::PickOff(params)) = params {
+                player.pick_off = params;
+            }
+            player.start().await;
+        }),
+        PlayerName::Seller => tokio::task::spawn(async move {
+            let params = match params {
+                Some(StrategyParams::Seller(params)) => params,
+                _ => SellerParams::default(),
+            };
+            let mut player: GenericPlayer = GenericPlayer::new(player_name, params.lower_frequency, params.higher_frequency, event_sender, order_sender);
+            player.seller = params;
+            player.start().await;
+        }),
+        PlayerName::Human => tokio::task::spawn(async move {
+            let mut player: HumanPlayer = HumanPlayer::new(player_name, event_sender, order_sender);
+            player.start().await;
+        }),
+        _ => {
+            let (lower_frequency, higher_frequency) = match player_name {
+                PlayerName::Spread => (1000, 2000),
+                PlayerName::Noisy => (4000, 8000),
+                _ => (2000, 4000),
+            };
+            tokio::task::spawn(async move {
+                let mut player: GenericPlayer = GenericPlayer::new(player_name, lower_frequency, higher_frequency, event_sender, order_sender);
+                player.start().await;
+            })
+        },
+    };
+    Some(handle)
+}
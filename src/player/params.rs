@@ -0,0 +1,309 @@
+use super::PlayerName;
+use serde::{Serialize, Deserialize};
+
+
+// =-= Strategy Parameters =-= //
+// - the numbers a strategy is tuned by, pulled out of its logic so they can be swept by the tuner (see `tuning/mod.rs`)
+// - every strategy keeps its own typed set, and lists them as flat `Param`s (name, value, range) for the tuner to search over
+// - the defaults are the numbers the strategies were written with
+// - `set` clamps to the range and `normalise` fixes up anything that can't hold on its own (e.g. the frequency range can't be empty,
+// time buckets have to stay in order), so any set the tuner comes up with can be played
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Param {
+    pub name: String,
+    pub value: i64,
+    pub min: i64,
+    pub max: i64,
+    pub step: i64,
+}
+
+impl Param {
+    pub fn new(name: &str, value: impl TryInto<i64>, min: i64, max: i64, step: i64) -> Self {
+        Self { name: name.to_string(), value: value.try_into().unwrap_or(max), min, max, step }
+    }
+
+    pub fn values(&self) -> Vec<i64> {
+        // every value on the grid, from min to max in steps
+        (self.min..=self.max).step_by(self.step.max(1) as usize).collect()
+    }
+}
+
+fn clamp(value: i64, min: i64, max: i64) -> i64 {
+    value.max(min).min(max)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SellPhase {
+    pub from: u64, // applies under the previous phase's start down to (but not at) this many seconds left, the first phase from it on up
+    pub market: usize, // hit any bid at or above this
+    pub limit: usize, // and offer at this
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SellerParams {
+    pub lower_frequency: u64,
+    pub higher_frequency: u64,
+    pub phases: [SellPhase; 4], // latest phase last, it always starts at 0
+}
+
+impl Default for SellerParams {
+    fn default() -> Self {
+        Self {
+            lower_frequency: 2000,
+            higher_frequency: 4000,
+            phases: [
+                SellPhase { from: 180, market: 6, limit: 8 },
+                SellPhase { from: 120, market: 5, limit: 6 },
+                SellPhase { from: 60, market: 4, limit: 6 },
+                SellPhase { from: 0, market: 3, limit: 4 },
+            ],
+        }
+    }
+}
+
+impl SellerParams {
+    pub fn phase(&self, seconds_left: u64) -> SellPhase {
+        // like the original `seconds_left > 120 && seconds_left < 180`, the second a phase starts at belongs to neither side
+        // of it and falls through to the last phase (e.g. exactly 120s left sells at 3 / 4 with the defaults)
+        if seconds_left >= self.phases[0].from {
+            return self.phases[0];
+        }
+        self.phases[1..3].iter().zip(self.phases.iter())
+            .find(|(phase, earlier)| seconds_left > phase.from && seconds_left < earlier.from)
+            .map(|(phase, _)| *phase)
+            .unwrap_or(self.phases[3])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceBucket {
+    pub below: u64, // applies under this many seconds left, down to the previous bucket
+    pub open: usize, // buy any ask under this
+    pub close: usize, // sell into any bid at or above this
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickOffParams {
+    pub buckets: [PriceBucket; 5], // earliest bucket last, it covers the rest of the round
+    pub max_inventory: usize, // stop picking up a suit once holding more than this
+    pub ask_floor: usize, // undercut asks above this
+}
+
+impl Default for PickOffParams {
+    fn default() -> Self {
+        Self {
+            buckets: [
+                PriceBucket { below: 20, open: 0, close: 0 },
+                PriceBucket { below: 40, open: 2, close: 3 },
+                PriceBucket { below: 60, open: 3, close: 4 },
+                PriceBucket { below: 120, open: 4, close: 6 },
+                PriceBucket { below: 240, open: 5, close: 8 },
+            ],
+            max_inventory: 2,
+            ask_floor: 5,
+        }
+    }
+}
+
+impl PickOffParams {
+    pub fn bucket(&self, seconds_left: u64) -> PriceBucket {
+        self.buckets.iter().find(|bucket| seconds_left < bucket.below).copied().unwrap_or(self.buckets[4])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiltParams {
+    pub lower_frequency: u64,
+    pub higher_frequency: u64,
+    pub dump_at: u64, // seconds left at which everything but the goal suit is dumped
+    pub dump_price: usize,
+    pub ask_floor: usize, // until then, undercut asks above this
+    pub take_at: usize, // lift goal suit asks at or below this
+    pub bid_cap: usize, // and improve goal suit bids below this
+}
+
+impl Default for TiltParams {
+    fn default() -> Self {
+        Self {
+            lower_frequency: 2000,
+            higher_frequency: 4000,
+            dump_at: 30,
+            dump_price: 3,
+            ask_floor: 4,
+            take_at: 5,
+            bid_cap: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy")]
+pub enum StrategyParams {
+    Seller(SellerParams),
+    PickOff(PickOffParams),
+    TiltInventory(TiltParams),
+}
+
+impl StrategyParams {
+    pub fn for_player(player_name: &PlayerName) -> Option<Self> {
+        // only the strategies that have been pulled apart so far
+        match player_name {
+            PlayerName::Seller => Some(StrategyParams::Seller(SellerParams::default())),
+            PlayerName::PickOff => Some(StrategyParams::PickOff(PickOffParams::default())),
+            PlayerName::TiltInventory => Some(StrategyParams::TiltInventory(TiltParams::default())),
+            _ => None,
+        }
+    }
+
+    pub fn params(&self) -> Vec<Param> {
+        match self {
+            StrategyParams::Seller(seller) => {
+                let mut params = vec![
+                    Param::new("lower_frequency", seller.lower_frequency, 250, 8000, 250),
+                    Param::new("higher_frequency", seller.higher_frequency, 500, 10000, 250),
+                ];
+                for (idx, phase) in seller.phases.iter().enumerate() {
+                    if idx < 3 {
+                        params.push(Param::new(&format!("phases[{}].from", idx), phase.from, 0, 240, 10));
+                    }
+                    params.push(Param::new(&format!("phases[{}].market", idx), phase.market, 1, 15, 1));
+                    params.push(Param::new(&format!("phases[{}].limit", idx), phase.limit, 1, 15, 1));
+                }
+                params
+            },
+            StrategyParams::PickOff(pick_off) => {
+                let mut params = Vec::new();
+                for (idx, bucket) in pick_off.buckets.iter().enumerate() {
+                    if idx < 4 {
+                        params.push(Param::new(&format!("buckets[{}].below", idx), bucket.below, 0, 240, 10));
+                    }
+                    params.push(Param::new(&format!("buckets[{}].open", idx), bucket.open, 0, 15, 1));
+                    params.push(Param::new(&format!("buckets[{}].close", idx), bucket.close, 0, 15, 1));
+                }
+                params.push(Param::new("max_inventory", pick_off.max_inventory, 0, 12, 1));
+                params.push(Param::new("ask_floor", pick_off.ask_floor, 1, 15, 1));
+                params
+            },
+            StrategyParams::TiltInventory(tilt) => vec![
+                Param::new("lower_frequency", tilt.lower_frequency, 250, 8000, 250),
+                Param::new("higher_frequency", tilt.higher_frequency, 500, 10000, 250),
+                Param::new("dump_at", tilt.dump_at, 0, 240, 10),
+                Param::new("dump_price", tilt.dump_price, 1, 15, 1),
+                Param::new("ask_floor", tilt.ask_floor, 1, 15, 1),
+                Param::new("take_at", tilt.take_at, 1, 15, 1),
+                Param::new("bid_cap", tilt.bid_cap, 1, 15, 1),
+            ],
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) -> Result<(), String> {
+        let param = self.params().into_iter().find(|param| param.name == name)
+            .ok_or_else(|| format!("unknown parameter '{}', pick from {}", name, self.params().iter().map(|param| param.name.clone()).collect::<Vec<_>>().join(", ")))?;
+        let value = clamp(value, param.min, param.max);
+        let (seconds, price) = (value as u64, value as usize);
+
+        match self {
+            StrategyParams::Seller(seller) => match name {
+                "lower_frequency" => seller.lower_frequency = seconds,
+                "higher_frequency" => seller.higher_frequency = seconds,
+                _ => {
+                    let (idx, field) = indexed(name, "phases")?;
+                    let phase = &mut seller.phases[idx];
+                    match field {
+                        "from" => phase.from = seconds,
+                        "market" => phase.market = price,
+                        _ => phase.limit = price,
+                    }
+                },
+            },
+            StrategyParams::PickOff(pick_off) => match name {
+                "max_inventory" => pick_off.max_inventory = price,
+                "ask_floor" => pick_off.ask_floor = price,
+                _ => {
+                    let (idx, field) = indexed(name, "buckets")?;
+                    let bucket = &mut pick_off.buckets[idx];
+                    match field {
+                        "below" => bucket.below = seconds,
+                        "open" => bucket.open = price,
+                        _ => bucket.close = price,
+                    }
+                },
+            },
+            StrategyParams::TiltInventory(tilt) => match name {
+                "lower_frequency" => tilt.lower_frequency = seconds,
+                "higher_frequency" => tilt.higher_frequency = seconds,
+                "dump_at" => tilt.dump_at = seconds,
+                "dump_price" => tilt.dump_price = price,
+                "ask_floor" => tilt.ask_floor = price,
+                "take_at" => tilt.take_at = price,
+                _ => tilt.bid_cap = price,
+            },
+        }
+        self.normalise();
+        Ok(())
+    }
+
+    pub fn normalise(&mut self) {
+        match self {
+            StrategyParams::Seller(seller) => {
+                seller.higher_frequency = seller.higher_frequency.max(seller.lower_frequency + 1);
+                // each phase starts before the next one, and the last always runs out the round
+                // (capped first, so there's room to keep them in order without leaving the range)
+                seller.phases[3].from = 0;
+                for idx in 0..3 {
+                    seller.phases[idx].from = seller.phases[idx].from.min(240 - idx as u64);
+                }
+                for idx in (0..3).rev() {
+                    seller.phases[idx].from = seller.phases[idx].from.max(seller.phases[idx + 1].from + 1);
+                }
+            },
+            StrategyParams::PickOff(pick_off) => {
+                for idx in 0..4 {
+                    pick_off.buckets[idx].below = pick_off.buckets[idx].below.min(237 + idx as u64);
+                }
+                for idx in 1..5 {
+                    pick_off.buckets[idx].below = pick_off.buckets[idx].below.max(pick_off.buckets[idx - 1].below + 1);
+                }
+            },
+            StrategyParams::TiltInventory(tilt) => {
+                tilt.higher_frequency = tilt.higher_frequency.max(tilt.lower_frequency + 1);
+            },
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        self.params().iter().map(|param| format!("{}={}", param.name, param.value)).collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn player_name(&self) -> PlayerName {
+        match self {
+            StrategyParams::Seller(_) => PlayerName::Seller,
+            StrategyParams::PickOff(_) => PlayerName::PickOff,
+            StrategyParams::TiltInventory(_) => PlayerName::TiltInventory,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        // how the set shows up in ratings and standings, "Seller" for the defaults and e.g. "Seller[phases[3].limit=4]" otherwise,
+        // so a tuned set is rated on its own instead of being mixed in with the strategy it came from
+        let name = String::from(self.player_name());
+        let Some(defaults) = StrategyParams::for_player(&self.player_name()) else { return name };
+        let changed: Vec<String> = self.params().iter().zip(defaults.params())
+            .filter(|(param, default)| param.value != default.value)
+            .map(|(param, _)| format!("{}={}", param.name, param.value))
+            .collect();
+        match changed.is_empty() {
+            true => name,
+            false => format!("{}[{}]", name, changed.join(" ")),
+        }
+    }
+}
+
+fn indexed<'a>(name: &'a str, list: &str) -> Result<(usize, &'a str), String> {
+    // "phases[2].limit" -> (2, "limit"), names have already been checked against `params`
+    let rest = name.strip_prefix(list).and_then(|rest| rest.strip_prefix('[')).ok_or_else(|| format!("unknown parameter '{}'", name))?;
+    let (idx, field) = rest.split_once("].").ok_or_else(|| format!("unknown parameter '{}'", name))?;
+    let idx = idx.parse::<usize>().map_err(|e| format!("unknown parameter '{}': {}", name, e))?;
+    Ok((idx, field))
+}
@@ -1,20 +1,21 @@
-use super::{Card, Direction, Book, Trade, Inventory, Order, Event, CL, PlayerName};
+use super::{Card, Direction, Book, Trade, Inventory, Order, Request, Event, AckStatus, CardTracker, PlayerName, TiltParams};
 use kanal::{AsyncSender};
 use tokio::sync::broadcast::{Sender, Receiver};
+use tokio::sync::broadcast::error::RecvError;
 use std::sync::Arc;
+use tracing::{debug, warn, error};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use tokio::sync::Mutex;
 use rand::Rng;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
-use std::time::Instant;
+use tokio::time::Instant;
 
 
 
 pub struct TiltInventory {
     pub name: PlayerName,
-    pub verbose: bool,
     pub timer: Arc<Mutex<Instant>>,
     pub spades_book: Arc<Mutex<Book>>,
     pub clubs_book: Arc<Mutex<Book>>,
@@ -22,36 +23,38 @@ pub struct TiltInventory {
     pub hearts_book: Arc<Mutex<Book>>,
     pub inventory: Arc<Mutex<Inventory>>,
     pub trades: Arc<Mutex<Vec<Trade>>>,
+    pub tracker: Arc<Mutex<CardTracker>>,
     pub highest_card: Arc<Mutex<Card>>,
     pub lower_frequency: u64,
     pub higher_frequency: u64,
+    pub params: TiltParams,
     pub event_receiver: Sender<Event>,
-    pub order_sender: Arc<AsyncSender<Order>>,
+    pub order_sender: Arc<AsyncSender<Request>>,
     pub trading: Arc<AtomicBool>,
 }
 
 impl TiltInventory {
     pub fn new(
         player_name: PlayerName,
-        verbose: bool,
         lower_frequency: u64,
         higher_frequency: u64,
         event_receiver: Sender<Event>,
-        order_sender: Arc<AsyncSender<Order>>,
+        order_sender: Arc<AsyncSender<Request>>,
     ) -> Self {
         Self {
-            name: player_name,
-            verbose,
-            timer: Arc::new(Mutex::new(std::time::Instant::now())),
+            name: player_name.clone(),
+            timer: Arc::new(Mutex::new(Instant::now())),
             spades_book: Arc::new(Mutex::new(Book::new())),
             clubs_book: Arc::new(Mutex::new(Book::new())),
             diamonds_book: Arc::new(Mutex::new(Book::new())),
             hearts_book: Arc::new(Mutex::new(Book::new())),
             inventory: Arc::new(Mutex::new(Inventory::new())),
             trades: Arc::new(Mutex::new(Vec::new())),
+            tracker: Arc::new(Mutex::new(CardTracker::new(player_name))),
             highest_card: Arc::new(Mutex::new(Card::Spade)),
             lower_frequency,
             higher_frequency,
+            params: TiltParams::default(),
             event_receiver,
             order_sender,
             trading: Arc::new(AtomicBool::new(false)),
@@ -72,16 +75,16 @@ impl TiltInventory {
                 continue;
             }
 
-            let seconds_left = 240 - self.timer.lock().await.elapsed().as_secs();
+            let seconds_left = 240u64.saturating_sub(self.timer.lock().await.elapsed().as_secs()); // the round can run a little past 240s before EndRound lands
 
-            let inventory = self.inventory.lock().await.clone();
+            let inventory = *self.inventory.lock().await;
 
             let spades_book = self.spades_book.lock().await.clone();
             let clubs_book = self.clubs_book.lock().await.clone();
             let diamonds_book = self.diamonds_book.lock().await.clone();
             let hearts_book = self.hearts_book.lock().await.clone();
 
-            println!("{}{:?} | Inventory |:| Spades: {} | Clubs: {} | Diamonds: {} | Hearts: {}{}", CL::Dull.get(), self.name, inventory.spades, inventory.clubs, inventory.diamonds, inventory.hearts, CL::End.get());
+            debug!(player = %self.name, spades = inventory.spades, clubs = inventory.clubs, diamonds = inventory.diamonds, hearts = inventory.hearts, "inventory");
 
             // only buy lowest card we were dealt and aggressively sell everything else
             let goal_suit = self.highest_card.lock().await.clone();
@@ -96,12 +99,12 @@ impl TiltInventory {
                     };
 
                     if current_inventory > 0 {
-                        if seconds_left > 30 {
-                            if book.ask.price > 4 {
-                                self.send_order(book.ask.price - 1, Direction::Sell, &card, &book).await;
+                        if seconds_left > self.params.dump_at {
+                            if book.ask.price > self.params.ask_floor {
+                                self.send_order(book.ask.price - 1, Direction::Sell, card, &book).await;
                             }
                         } else {
-                            self.send_order(3, Direction::Sell, &card, &book).await; // 3 since this is the break-point between a blanket buy-all strategy becoming profitable buying up equal amounts of inventory at 3
+                            self.send_order(self.params.dump_price, Direction::Sell, card, &book).await; // 3 by default since this is the break-point between a blanket buy-all strategy becoming profitable buying up equal amounts of inventory at 3
                         }
                     }
                 }
@@ -114,12 +117,12 @@ impl TiltInventory {
                 Card::Heart => hearts_book,
             };
 
-            if book.ask.price <= 5 {
+            if book.ask.price <= self.params.take_at {
                 // send market buy
                 self.send_order(book.ask.price, Direction::Buy, &goal_suit, &book).await;
             }
 
-            if book.bid.price < 8 {
+            if book.bid.price < self.params.bid_cap {
                 self.send_order(book.bid.price + 1, Direction::Buy, &goal_suit, &book).await;
             }
             
@@ -155,12 +158,10 @@ impl TiltInventory {
                 card: card.clone(),
             };
     
-            if self.verbose {
-                println!("{:?} |:| Sending order: {:?}", self.name, order);
-            }
+            debug!(player = %self.name, suit = ?order.card, direction = ?order.direction, price = order.price, "sending order");
     
-            if let Err(e) = self.order_sender.send(order).await {
-                println!("[!] {:?} |:| Error sending order: {:?}", self.name, e);
+            if let Err(e) = self.order_sender.send(Request::Order(order)).await {
+                error!(player = %self.name, error = ?e, "unable to send order");
             }
         }
         
@@ -178,28 +179,41 @@ impl TiltInventory {
 
         let inventory: Arc<Mutex<Inventory>> = self.inventory.clone();
         let trades: Arc<Mutex<Vec<Trade>>> = self.trades.clone();
+        let tracker: Arc<Mutex<CardTracker>> = self.tracker.clone();
         let trading: Arc<AtomicBool> = self.trading.clone();
 
         let name: PlayerName = self.name.clone();
-        let verbose: bool = self.verbose;
         let timer = self.timer.clone();
         let highest_card = self.highest_card.clone();
         
         tokio::task::spawn(async move {
             loop {
 
-                let event = event_receiver.recv().await.unwrap();
+                let event = match event_receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => { // a quiet console lets the match maker outrun us, the next update has the latest books
+                        warn!(player = %name, skipped, "lagged behind the match maker");
+                        continue;
+                    },
+                    Err(RecvError::Closed) => break,
+                };
                 match event {
                     Event::Update(update) => {
                         if let Some(trade) = update.trade { // push trade for historical reasons (if we want to analyze) & update inventory
                             let mut trade_lock = trades.lock().await;
                             trade_lock.push(trade.clone());
+                            tracker.lock().await.record_trade(&trade);
 
                             let mut inventory_lock = inventory.lock().await;
-                            if trade.buyer == name {
-                                inventory_lock.change(trade.card, true);
+                            let change = if trade.buyer == name {
+                                inventory_lock.change(trade.card, true)
                             } else if trade.seller == name {
-                                inventory_lock.change(trade.card, false);
+                                inventory_lock.change(trade.card, false)
+                            } else {
+                                Ok(())
+                            };
+                            if let Err(e) = change {
+                                error!(player = %name, error = %e, "inventory out of sync with the tape");
                             }
                         }
 
@@ -219,7 +233,8 @@ impl TiltInventory {
                     }
                     Event::DealCards(players_inventory) => {
                         let mut inventory_lock = inventory.lock().await;
-                        *inventory_lock = players_inventory.get(&name).unwrap().clone();
+                        *inventory_lock = *players_inventory.get(&name).unwrap();
+                        tracker.lock().await.deal(*inventory_lock, players_inventory.keys().cloned().collect());
 
                         // doesn't take into account ties for lowest card
                         let mut highest = (Card::Spade, 0);
@@ -238,9 +253,7 @@ impl TiltInventory {
                         let goal_suit = highest.0.get_goal_suit();
                         *highest_card.lock().await = goal_suit;
                         
-                        if verbose {
-                            println!("{}[+] {:?} |:| Received cards: {:?}{}", CL::DullGreen.get(), name, inventory_lock, CL::End.get());
-                        }
+                        debug!(player = %name, hand = ?inventory_lock, "received cards");
                         
                         trading.store(true, Ordering::Release);
                         let mut timer_lock = timer.lock().await;
@@ -248,6 +261,13 @@ impl TiltInventory {
                     },
                     Event::EndRound => {
                         trading.store(false, Ordering::Release);
+                    },
+                    Event::Ack(ack) => {
+                        if let AckStatus::Rejected(reason) = ack.status {
+                            if ack.player_name == name {
+                                debug!(player = %name, seq = ack.seq, suit = ?ack.card, reason = ?reason, "order rejected");
+                            }
+                        }
                     }
                 }
 
@@ -0,0 +1,120 @@
+use super::CL;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+
+// =-= Ratings =-= //
+// - raw points swing with the deal, so strategies are rated on how they place against each other instead
+// - multiplayer Elo: every result is scored as a head-to-head against each other player at the table
+// (1 for finishing ahead, 0.5 for a tie, 0 for finishing behind), and the rating moves by K / (players - 1) times the
+// difference between that and what the ratings expected
+// - keyed by a label, which is just the strategy's name unless the runner says otherwise (e.g. "Spread[edge=2]")
+// - saved as JSON, so ratings keep building up across batches
+
+pub const INITIAL_RATING: f64 = 1500.0;
+pub const K_FACTOR: f64 = 32.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub rating: f64,
+    pub results: usize, // rounds or games, whichever the ratings are updated on
+    pub wins: usize, // finished first (ties included)
+    pub points: i64, // summed over every result, for the leaderboard
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self { rating: INITIAL_RATING, results: 0, wins: 0, points: 0 }
+    }
+}
+
+impl Rating {
+    pub fn avg_points(&self) -> f64 {
+        if self.results == 0 { return 0.0 }
+        self.points as f64 / self.results as f64
+    }
+}
+
+pub fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ratings {
+    pub players: HashMap<String, Rating>,
+}
+
+impl Ratings {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        // no file yet just means nobody has been rated
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(io::Error::from),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get(&self, label: &str) -> Rating {
+        self.players.get(label).copied().unwrap_or_default()
+    }
+
+    pub fn update(&mut self, standings: &[(String, i64)]) {
+        // every rating moves off the ratings from before this result, so the order of the standings doesn't matter
+        if standings.len() < 2 {
+            return;
+        }
+        let before: Vec<f64> = standings.iter().map(|(label, _)| self.get(label).rating).collect();
+        let best = standings.iter().map(|(_, points)| *points).max().unwrap_or(0);
+        let k = K_FACTOR / (standings.len() - 1) as f64;
+
+        for (idx, (label, points)) in standings.iter().enumerate() {
+            let mut actual = 0.0;
+            let mut expected = 0.0;
+            for (other, (_, other_points)) in standings.iter().enumerate() {
+                if other == idx {
+                    continue;
+                }
+                actual += match points.cmp(other_points) {
+                    std::cmp::Ordering::Greater => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Less => 0.0,
+                };
+                expected += expected_score(before[idx], before[other]);
+            }
+
+            let rating = self.players.entry(label.clone()).or_default();
+            rating.rating += k * (actual - expected);
+            rating.results += 1;
+            rating.points += points;
+            if *points == best {
+                rating.wins += 1;
+            }
+        }
+    }
+
+    pub fn leaderboard(&self) -> Vec<(String, Rating)> {
+        let mut leaderboard: Vec<(String, Rating)> = self.players.iter().map(|(label, rating)| (label.clone(), *rating)).collect();
+        leaderboard.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating).then_with(|| a.0.cmp(&b.0)));
+        leaderboard
+    }
+
+    pub fn print_leaderboard(&self) {
+        console!("=---= Leaderboard =---=");
+        for (rank, (label, rating)) in self.leaderboard().iter().enumerate() {
+            narrate!(
+                info!(rank = rank + 1, player = %label, rating = rating.rating, results = rating.results, wins = rating.wins, avg_points = rating.avg_points(), "leaderboard"),
+                "{}{:>2}. {}{:<24}{} |:| Rating: {}{:.0}{} | Results: {} | Wins: {} | Avg points: {:+.1}{}",
+                CL::Dull.get(), rank + 1, CL::DimLightBlue.get(), label, CL::Dull.get(),
+                CL::LimeGreen.get(), rating.rating, CL::Dull.get(), rating.results, rating.wins, rating.avg_points(), CL::End.get(),
+            );
+        }
+        console!();
+    }
+}
@@ -0,0 +1,341 @@
+use super::analytics::read_table;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub mod svg;
+pub use svg::*;
+
+
+// =-= Report =-= //
+// - turns a session recorded with `--export` (see `analytics/tables.rs`) into one self-contained HTML page
+// - up top: every player's points across rounds and the session totals
+// - per round: each suit's bid and ask over the round with its trades marked by player (filled in the colour of whoever
+// crossed the spread, ringed in the colour of whose quote was hit), and each player's hand over the round
+// - the common and goal suits (and the results, which give them away) are folded away behind a reveal, so the charts can be read blind first
+// - times are seconds into the round, counted from the round's first book update
+
+pub const SUITS: [&str; 4] = ["Spade", "Club", "Diamond", "Heart"];
+pub const SUIT_COLORS: [&str; 4] = ["#3a3a3a", "#2a9d8f", "#e9a23b", "#d62828"];
+pub const PLAYER_COLORS: [&str; 10] = ["#4e79a7", "#f28e2b", "#59a14f", "#b07aa1", "#e15759", "#76b7b2", "#edc948", "#9c755f", "#ff9da7", "#bab0ac"];
+pub const ROUND_SECONDS: f64 = 240.0;
+
+#[derive(Debug, Clone)]
+pub struct TradeRow {
+    pub round: u32,
+    pub seq: u64,
+    pub timestamp: u64,
+    pub suit: String,
+    pub price: usize,
+    pub buyer: String,
+    pub seller: String,
+    pub aggressor: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuoteRow {
+    pub round: u32,
+    pub timestamp: u64,
+    pub suit: String,
+    pub bid: Option<usize>,
+    pub ask: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DealRow {
+    pub round: u32,
+    pub player: String,
+    pub hand: [usize; 4], // in `SUITS` order
+    pub common_suit: String,
+    pub goal_suit: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultRow {
+    pub round: u32,
+    pub player: String,
+    pub ante: i64,
+    pub trading: i64,
+    pub goal_payout: i64,
+    pub bonus: i64,
+    pub pnl: i64,
+    pub trades: usize,
+    pub points: i64,
+}
+
+fn get<T: FromStr>(row: &HashMap<String, String>, key: &str) -> io::Result<T> {
+    row.get(key)
+        .and_then(|value| value.parse::<T>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("bad or missing '{}' in {:?}", key, row)))
+}
+
+fn text(row: &HashMap<String, String>, key: &str) -> String {
+    row.get(key).cloned().unwrap_or_default()
+}
+
+fn optional(row: &HashMap<String, String>, key: &str) -> Option<usize> {
+    row.get(key).and_then(|value| value.parse::<usize>().ok())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    pub trades: Vec<TradeRow>,
+    pub quotes: Vec<QuoteRow>,
+    pub deals: Vec<DealRow>,
+    pub results: Vec<ResultRow>,
+    pub players: Vec<String>, // in seat order
+}
+
+impl Session {
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut session = Session::default();
+        for row in read_table(&dir.join("trades.csv"))? {
+            session.trades.push(TradeRow {
+                round: get(&row, "round")?,
+                seq: get(&row, "seq")?,
+                timestamp: get(&row, "timestamp")?,
+                suit: text(&row, "suit"),
+                price: get(&row, "price")?,
+                buyer: text(&row, "buyer"),
+                seller: text(&row, "seller"),
+                aggressor: text(&row, "aggressor"),
+            });
+        }
+        for row in read_table(&dir.join("quotes.csv"))? {
+            session.quotes.push(QuoteRow {
+                round: get(&row, "round")?,
+                timestamp: get(&row, "timestamp")?,
+                suit: text(&row, "suit"),
+                bid: optional(&row, "bid"),
+                ask: optional(&row, "ask"),
+            });
+        }
+        for row in read_table(&dir.join("deals.csv"))? {
+            let player = text(&row, "player");
+            if !session.players.contains(&player) {
+                session.players.push(player.clone());
+            }
+            session.deals.push(DealRow {
+                round: get(&row, "round")?,
+                player,
+                hand: [get(&row, "spades")?, get(&row, "clubs")?, get(&row, "diamonds")?, get(&row, "hearts")?],
+                common_suit: text(&row, "common_suit"),
+                goal_suit: text(&row, "goal_suit"),
+            });
+        }
+        for row in read_table(&dir.join("results.csv"))? {
+            session.results.push(ResultRow {
+                round: get(&row, "round")?,
+                player: text(&row, "player"),
+                ante: get(&row, "ante")?,
+                trading: get(&row, "trading")?,
+                goal_payout: get(&row, "goal_payout")?,
+                bonus: get(&row, "bonus")?,
+                pnl: get(&row, "pnl")?,
+                trades: get(&row, "trades")?,
+                points: get(&row, "points")?,
+            });
+        }
+        session.trades.sort_by_key(|trade| (trade.round, trade.seq));
+        session.quotes.sort_by_key(|quote| (quote.round, quote.timestamp));
+        Ok(session)
+    }
+
+    pub fn rounds(&self) -> Vec<u32> {
+        // only rounds that were settled, a round cut short has no results
+        let mut rounds: Vec<u32> = self.results.iter().map(|result| result.round).collect();
+        rounds.dedup();
+        rounds
+    }
+
+    pub fn round_start(&self, round: u32) -> u64 {
+        self.quotes.iter().filter(|quote| quote.round == round).map(|quote| quote.timestamp)
+            .chain(self.trades.iter().filter(|trade| trade.round == round).map(|trade| trade.timestamp))
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn player_color(&self, player: &str) -> &'static str {
+        let idx = self.players.iter().position(|other| other == player).unwrap_or(self.players.len());
+        PLAYER_COLORS[idx % PLAYER_COLORS.len()]
+    }
+}
+
+fn seconds(timestamp: u64, start: u64) -> f64 {
+    timestamp.saturating_sub(start) as f64 / 1_000_000.0
+}
+
+fn time_ticks(x_end: f64) -> Vec<f64> {
+    (0..=(x_end as usize)).step_by(60).map(|tick| tick as f64).collect()
+}
+
+pub fn price_chart(session: &Session, round: u32, suit: &str) -> String {
+    let start = session.round_start(round);
+    let quotes: Vec<&QuoteRow> = session.quotes.iter().filter(|quote| quote.round == round && quote.suit == suit).collect();
+    let trades: Vec<&TradeRow> = session.trades.iter().filter(|trade| trade.round == round && trade.suit == suit).collect();
+
+    let last = quotes.iter().map(|quote| seconds(quote.timestamp, start))
+        .chain(trades.iter().map(|trade| seconds(trade.timestamp, start)))
+        .fold(ROUND_SECONDS, f64::max);
+    // scaled to the trades and bids, an ask far above them (e.g. 98 into an empty book) is pinned to the top edge
+    let top = quotes.iter().filter_map(|quote| quote.bid)
+        .chain(trades.iter().map(|trade| trade.price))
+        .max().unwrap_or(0).max(13) as f64 + 2.0;
+
+    let mut chart = Chart::new(360.0, 170.0, last, 0.0, top);
+    chart.axes(&time_ticks(last), &|tick| format!("{}s", tick));
+    let bids: Vec<(f64, Option<f64>)> = quotes.iter().map(|quote| (seconds(quote.timestamp, start), quote.bid.map(|bid| bid as f64))).collect();
+    let asks: Vec<(f64, Option<f64>)> = quotes.iter().map(|quote| (seconds(quote.timestamp, start), quote.ask.map(|ask| (ask as f64).min(top)))).collect();
+    chart.step_line(&bids, last, "#59a14f", 1.5);
+    chart.step_line(&asks, last, "#e15759", 1.5);
+    for trade in trades.iter() {
+        let (taker, maker) = if trade.aggressor == "Buy" { (&trade.buyer, &trade.seller) } else { (&trade.seller, &trade.buyer) };
+        let time = seconds(trade.timestamp, start);
+        let title = format!("{:.0}s |:| {} buys from {} at {} ({} crossed)", time, trade.buyer, trade.seller, trade.price, taker);
+        chart.dot(time, trade.price as f64, 4.0, session.player_color(taker), session.player_color(maker), &title);
+    }
+    chart.render(&format!("{}s <span class=\"dull\">{} trades</span>", suit, trades.len()))
+}
+
+pub fn inventory_chart(session: &Session, round: u32, player: &str) -> String {
+    let start = session.round_start(round);
+    let Some(deal) = session.deals.iter().find(|deal| deal.round == round && deal.player == player) else { return String::new() };
+
+    let mut hand = deal.hand;
+    let mut history: Vec<(f64, [usize; 4])> = vec![(0.0, hand)];
+    for trade in session.trades.iter().filter(|trade| trade.round == round) {
+        let Some(suit) = SUITS.iter().position(|suit| *suit == trade.suit) else { continue };
+        if trade.buyer == player {
+            hand[suit] += 1;
+        }
+        if trade.seller == player {
+            hand[suit] = hand[suit].saturating_sub(1);
+        }
+        history.push((seconds(trade.timestamp, start), hand));
+    }
+
+    let last = history.iter().map(|(time, _)| *time).fold(ROUND_SECONDS, f64::max);
+    let top = history.iter().flat_map(|(_, hand)| hand.iter()).copied().max().unwrap_or(0).max(4) as f64 + 1.0;
+    let mut chart = Chart::new(240.0, 130.0, last, 0.0, top);
+    chart.axes(&time_ticks(last), &|tick| format!("{}s", tick));
+    for (suit, color) in SUIT_COLORS.iter().enumerate() {
+        let points: Vec<(f64, Option<f64>)> = history.iter().map(|(time, hand)| (*time, Some(hand[suit] as f64))).collect();
+        chart.step_line(&points, last, color, 1.5);
+    }
+    chart.render(&format!("<span style=\"color:{}\">&#9679;</span> {}", session.player_color(player), escape(player)))
+}
+
+pub fn points_chart(session: &Session) -> String {
+    let rounds = session.rounds();
+    let mut chart_lines: Vec<(String, Vec<(f64, f64)>)> = Vec::new();
+    for player in session.players.iter() {
+        let results: Vec<&ResultRow> = session.results.iter().filter(|result| result.player == *player).collect();
+        let Some(first) = results.first() else { continue };
+        let mut points = vec![(0.0, (first.points - first.pnl) as f64)];
+        points.extend(results.iter().enumerate().map(|(idx, result)| ((idx + 1) as f64, result.points as f64)));
+        chart_lines.push((player.clone(), points));
+    }
+
+    let values = chart_lines.iter().flat_map(|(_, points)| points.iter().map(|(_, y)| *y));
+    let (low, high) = values.fold((f64::MAX, f64::MIN), |(low, high), y| (low.min(y), high.max(y)));
+    let (low, high) = if low > high { (0.0, 1.0) } else { (low - 10.0, high + 10.0) };
+    let mut chart = Chart::new(720.0, 240.0, rounds.len().max(1) as f64, low, high);
+    let x_ticks: Vec<f64> = (0..=rounds.len()).map(|tick| tick as f64).collect();
+    chart.axes(&x_ticks, &|tick| if tick == 0.0 { String::from("start") } else { format!("R{}", tick as usize - 1) });
+    for (player, points) in chart_lines.iter() {
+        chart.line(points, session.player_color(player), 2.0);
+        for (round, value) in points.iter() {
+            chart.dot(*round, *value, 3.0, session.player_color(player), session.player_color(player), &format!("{}: {}", player, value));
+        }
+    }
+    chart.render("Points after each round")
+}
+
+pub fn legend(session: &Session) -> String {
+    let players: Vec<String> = session.players.iter()
+        .map(|player| format!("<span><span style=\"color:{}\">&#9679;</span> {}</span>", session.player_color(player), escape(player)))
+        .collect();
+    let suits: Vec<String> = SUITS.iter().zip(SUIT_COLORS.iter())
+        .map(|(suit, color)| format!("<span><span style=\"color:{}\">&#9473;</span> {}s</span>", color, suit))
+        .collect();
+    format!("<div class=\"legend\">{}</div><div class=\"legend dull\">{} &middot; prices: <span style=\"color:#59a14f\">&#9473;</span> bid <span style=\"color:#e15759\">&#9473;</span> ask, trades filled by who crossed and ringed by whose quote was hit</div>", players.join(""), suits.join(""))
+}
+
+pub fn session_table(session: &Session) -> String {
+    let mut rows = String::new();
+    for player in session.players.iter() {
+        let results: Vec<&ResultRow> = session.results.iter().filter(|result| result.player == *player).collect();
+        let pnl: i64 = results.iter().map(|result| result.pnl).sum();
+        let trades: usize = results.iter().map(|result| result.trades).sum();
+        let points = results.last().map(|result| result.points).unwrap_or(0);
+        let class = if pnl > 0 { "up" } else if pnl < 0 { "down" } else { "" };
+        rows += &format!(
+            "<tr><td><span style=\"color:{}\">&#9679;</span> {}</td><td>{}</td><td class=\"{}\">{:+}</td><td>{}</td></tr>",
+            session.player_color(player), escape(player), trades, class, pnl, points,
+        );
+    }
+    format!("<table><tr><th>Player</th><th>Trades</th><th>PnL</th><th>Points</th></tr>{}</table>", rows)
+}
+
+pub fn round_section(session: &Session, round: u32) -> String {
+    let deal = session.deals.iter().find(|deal| deal.round == round);
+    let trades = session.trades.iter().filter(|trade| trade.round == round).count();
+    let prices: Vec<String> = SUITS.iter().map(|suit| price_chart(session, round, suit)).collect();
+    let hands: Vec<String> = session.players.iter().map(|player| inventory_chart(session, round, player)).collect();
+
+    let mut results = String::new();
+    for result in session.results.iter().filter(|result| result.round == round) {
+        let dealt = session.deals.iter().find(|deal| deal.round == round && deal.player == result.player)
+            .map(|deal| deal.hand.iter().map(|count| count.to_string()).collect::<Vec<_>>().join(" / "))
+            .unwrap_or_default();
+        let class = if result.pnl > 0 { "up" } else if result.pnl < 0 { "down" } else { "" };
+        results += &format!(
+            "<tr><td><span style=\"color:{}\">&#9679;</span> {}</td><td>{}</td><td>{}</td><td>{}</td><td>{:+}</td><td>{}</td><td>{}</td><td class=\"{}\">{:+}</td><td>{}</td></tr>",
+            session.player_color(&result.player), escape(&result.player), dealt, result.trades, result.ante, result.trading,
+            result.goal_payout, result.bonus, class, result.pnl, result.points,
+        );
+    }
+    let (common_suit, goal_suit) = deal.map(|deal| (deal.common_suit.clone(), deal.goal_suit.clone())).unwrap_or_default();
+
+    format!(
+        "<section><h2>Round {} <span class=\"dull\">{} trades</span></h2>\
+        <div class=\"charts\">{}</div>\
+        <h3>Hands</h3><div class=\"charts\">{}</div>\
+        <details><summary>Reveal the suits</summary>\
+        <p>Common suit: <b>{}s</b> &middot; Goal suit: <b class=\"goal\">{}s</b></p>\
+        <table><tr><th>Player</th><th>Dealt (&spades; &clubs; &diams; &hearts;)</th><th>Trades</th><th>Ante</th><th>Trading</th><th>Goal payout</th><th>Bonus</th><th>PnL</th><th>Points</th></tr>{}</table>\
+        </details></section>",
+        round, trades, prices.join(""), hands.join(""), escape(&common_suit), escape(&goal_suit), results,
+    )
+}
+
+pub fn render(session: &Session, title: &str) -> String {
+    let rounds: Vec<String> = session.rounds().into_iter().map(|round| round_section(session, round)).collect();
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title><style>{style}</style></head><body>\
+        <h1>{title} <span class=\"dull\">{rounds} rounds, {trades} trades</span></h1>{legend}\
+        <section><div class=\"charts\">{points}{table}</div></section>{sections}</body></html>",
+        title = escape(title), style = STYLE, rounds = rounds.len(), trades = session.trades.len(), legend = legend(session),
+        points = points_chart(session), table = session_table(session), sections = rounds.join(""),
+    )
+}
+
+pub fn write_report(dir: &Path, output: Option<PathBuf>) -> io::Result<PathBuf> {
+    let session = Session::load(dir)?;
+    let output = output.unwrap_or_else(|| dir.join("report.html"));
+    let title = format!("Figgie session {}", dir.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default());
+    fs::write(&output, render(&session, &title))?;
+    Ok(output)
+}
+
+const STYLE: &str = "\
+body{font-family:-apple-system,'Segoe UI',Helvetica,sans-serif;margin:24px;color:#222;background:#fafafa}\
+h1,h2{font-weight:600}h3{font-weight:500;margin:8px 0}.dull{color:#888;font-weight:400;font-size:.8em}\
+section{background:#fff;border:1px solid #e4e4e4;border-radius:6px;padding:12px 16px;margin:16px 0}\
+.charts{display:flex;flex-wrap:wrap;gap:8px;align-items:flex-start}figure{margin:0}figcaption{font-size:.9em;margin-left:34px}\
+.legend{display:flex;flex-wrap:wrap;gap:14px;margin:6px 0}.grid{stroke:#eee}.axis{stroke:#bbb}.tick{font-size:9px;fill:#888}\
+table{border-collapse:collapse;font-size:.9em;margin:8px 0}td,th{padding:3px 10px;text-align:right}td:first-child,th:first-child{text-align:left}\
+th{border-bottom:1px solid #ddd;font-weight:500}.up{color:#2a9d4f}.down{color:#d62828}.goal{color:#2a9d4f}\
+details{margin-top:8px}summary{cursor:pointer;color:#4e79a7}";
@@ -0,0 +1,104 @@
+// =-= SVG Charts =-= //
+// - just enough of a charting library for the report: axes, step lines, lines and dots, drawn straight into an inline <svg>
+// - no scripts and nothing fetched, so the report opens anywhere, hovering a dot shows its <title>
+
+const LEFT: f64 = 34.0;
+const RIGHT: f64 = 8.0;
+const TOP: f64 = 8.0;
+const BOTTOM: f64 = 22.0;
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+pub fn ticks(min: f64, max: f64) -> Vec<f64> {
+    // round numbers, about five of them
+    let span = (max - min).max(1.0);
+    let step = [1.0, 2.0, 5.0, 10.0, 20.0, 25.0, 50.0, 100.0, 200.0, 250.0, 500.0, 1000.0]
+        .into_iter()
+        .find(|step| span / step <= 6.0)
+        .unwrap_or(span / 5.0);
+    let mut tick = (min / step).ceil() * step;
+    let mut ticks = Vec::new();
+    while tick <= max {
+        ticks.push(tick);
+        tick += step;
+    }
+    ticks
+}
+
+pub struct Chart {
+    pub width: f64,
+    pub height: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub body: String,
+}
+
+impl Chart {
+    pub fn new(width: f64, height: f64, x_max: f64, y_min: f64, y_max: f64) -> Self {
+        Self { width, height, x_max: x_max.max(1.0), y_min, y_max: y_max.max(y_min + 1.0), body: String::new() }
+    }
+
+    pub fn x(&self, x: f64) -> f64 {
+        LEFT + x / self.x_max * (self.width - LEFT - RIGHT)
+    }
+
+    pub fn y(&self, y: f64) -> f64 {
+        self.height - BOTTOM - (y - self.y_min) / (self.y_max - self.y_min) * (self.height - TOP - BOTTOM)
+    }
+
+    pub fn axes(&mut self, x_ticks: &[f64], x_label: &dyn Fn(f64) -> String) {
+        let mut axes = String::new();
+        for tick in ticks(self.y_min, self.y_max) {
+            let y = self.y(tick);
+            axes += &format!("<line class=\"grid\" x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>", LEFT, y, self.width - RIGHT, y);
+            axes += &format!("<text class=\"tick\" x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>", LEFT - 4.0, y + 3.0, tick);
+        }
+        for tick in x_ticks.iter() {
+            let x = self.x(*tick);
+            axes += &format!("<text class=\"tick\" x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>", x, self.height - 6.0, escape(&x_label(*tick)));
+        }
+        axes += &format!(
+            "<line class=\"axis\" x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>",
+            LEFT, self.height - BOTTOM, self.width - RIGHT, self.height - BOTTOM,
+        );
+        self.body = axes + &self.body;
+    }
+
+    pub fn step_line(&mut self, points: &[(f64, Option<f64>)], x_end: f64, color: &str, width: f64) {
+        // holds each value until the next point, with a gap wherever there's no value (e.g. an empty side of the book)
+        let mut path = String::new();
+        for (idx, (x, value)) in points.iter().enumerate() {
+            let Some(value) = value else { continue };
+            let next = points.get(idx + 1).map(|(x, _)| *x).unwrap_or(x_end);
+            path += &format!("M{:.1},{:.1}H{:.1}", self.x(*x), self.y(*value), self.x(next));
+            if let Some((_, Some(next_value))) = points.get(idx + 1) {
+                path += &format!("V{:.1}", self.y(*next_value));
+            }
+        }
+        if !path.is_empty() {
+            self.body += &format!("<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>", path, color, width);
+        }
+    }
+
+    pub fn line(&mut self, points: &[(f64, f64)], color: &str, width: f64) {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{:.1},{:.1}", self.x(*x), self.y(*y))).collect();
+        self.body += &format!("<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>", points.join(" "), color, width);
+    }
+
+    pub fn dot(&mut self, x: f64, y: f64, radius: f64, fill: &str, stroke: &str, title: &str) {
+        self.body += &format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"2\"><title>{}</title></circle>",
+            self.x(x), self.y(y), radius, fill, stroke, escape(title),
+        );
+    }
+
+    pub fn render(&self, title: &str) -> String {
+        format!(
+            "<figure><figcaption>{}</figcaption><svg viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\">{}</svg></figure>",
+            title, self.width, self.height, self.width, self.height, self.body,
+        )
+    }
+}
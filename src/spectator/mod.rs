@@ -0,0 +1,259 @@
+use super::{Card, Book, Inventory, Trade, Event, Update, Snapshot, PlayerName};
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, Sender};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch::Receiver;
+use std::collections::HashMap;
+use tracing::{info, warn, error};
+
+
+// =-= Spectator =-= //
+// - a read-only window into the match maker over localhost HTTP, for watching games from a browser
+// - `GET /` is the page (see `index.html`), `GET /snapshot` the table as JSON, `GET /events` a server-sent event stream
+// - `GET /metrics` is the match maker's own health in the Prometheus text format, see `match_maker/metrics.rs`
+// - spectators see what anyone sitting at the table would: books, trades, points, the clock and how many cards each seat holds
+// - hands and the common/goal suits only show up once the round is settled, acks never leave (they're the seat's business)
+// - nothing a spectator sends reaches the match maker
+
+const PAGE: &str = include_str!("index.html");
+const KEEP_ALIVE: u64 = 15; // seconds between comments on a quiet stream, so a closed tab is noticed
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeatView {
+    pub name: PlayerName,
+    pub points: usize,
+    pub change: Option<i64>, // from the last settled round
+    pub cards: usize,
+    pub hand: Option<Inventory>, // only once the round is settled
+    pub throttled: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableView {
+    pub round: u32,
+    pub trading: bool,
+    pub seconds_left: u64,
+    pub round_seconds: u64,
+    pub revealed: bool,
+    pub common_suit: Option<Card>,
+    pub goal_suit: Option<Card>,
+    pub books: HashMap<Card, Book>,
+    pub players: Vec<SeatView>,
+    pub trades: Vec<Trade>, // the end of this round's tape, the stream has all of them
+    pub trade_count: usize,
+    pub orders: usize,
+    pub queue: usize,
+}
+
+impl TableView {
+    pub fn new(snapshot: &Snapshot) -> Self {
+        // the match maker only fills in the suits once a round is settled, so that's when the hands come out too
+        let revealed = snapshot.goal_suit.is_some();
+        let players = snapshot.player_names.iter()
+            .map(|player_name| {
+                let inventory = snapshot.inventories.get(player_name).copied().unwrap_or_default();
+                SeatView {
+                    name: player_name.clone(),
+                    points: snapshot.points.get(player_name).copied().unwrap_or(0),
+                    change: snapshot.point_changes.get(player_name).copied(),
+                    cards: inventory.total(),
+                    hand: if revealed { Some(inventory) } else { None },
+                    throttled: snapshot.throttled.get(player_name).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+
+        Self {
+            round: snapshot.round,
+            trading: snapshot.trading,
+            seconds_left: snapshot.seconds_left(),
+            round_seconds: snapshot.round_duration.as_secs(),
+            revealed,
+            common_suit: snapshot.common_suit.clone(),
+            goal_suit: snapshot.goal_suit.clone(),
+            books: snapshot.books.clone(),
+            players,
+            trades: snapshot.trades.clone(),
+            trade_count: snapshot.trade_count,
+            orders: snapshot.orders,
+            queue: snapshot.queue,
+        }
+    }
+}
+
+// one JSON object per server-sent event, tagged with "type" like the gateway's messages
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpectatorMessage {
+    Deal { cards: HashMap<PlayerName, usize> }, // how many cards each seat was dealt, never which
+    Update(Box<Update>),
+    EndRound,
+    Reveal(Box<TableView>), // the settled table, hands and suits included
+    Lagged { skipped: u64 },
+}
+
+pub fn to_spectator_message(event: Event) -> Option<SpectatorMessage> {
+    // None when the event isn't for spectators
+    let message = match event {
+        Event::Update(update) => SpectatorMessage::Update(update),
+        Event::DealCards(players_inventory) => SpectatorMessage::Deal {
+            cards: players_inventory.into_iter().map(|(player_name, inventory)| (player_name, inventory.total())).collect(),
+        },
+        Event::EndRound => SpectatorMessage::EndRound,
+        Event::Ack(_) => return None,
+    };
+    Some(message)
+}
+
+pub struct Spectator {
+    pub address: String,
+    pub event_sender: Sender<Event>,
+    pub snapshot_receiver: Receiver<Snapshot>,
+}
+
+impl Spectator {
+    pub fn new(address: String, event_sender: Sender<Event>, snapshot_receiver: Receiver<Snapshot>) -> Self {
+        Self {
+            address,
+            event_sender,
+            snapshot_receiver,
+        }
+    }
+
+
+
+    pub async fn start(&mut self) {
+        let listener = match TcpListener::bind(&self.address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!(address = %self.address, error = %e, "unable to listen");
+                return;
+            }
+        };
+        info!("watch at http://{}", self.address);
+        self.serve(listener).await;
+    }
+
+    pub async fn serve(&self, listener: TcpListener) {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!(error = %e, "unable to accept connection");
+                    continue;
+                }
+            };
+
+            // subscribed before the request is read, so a stream doesn't miss what happens while it's being set up
+            let event_receiver = self.event_sender.subscribe();
+            let snapshot_receiver = self.snapshot_receiver.clone();
+            tokio::task::spawn(async move {
+                handle_connection(stream, event_receiver, snapshot_receiver).await;
+            });
+        }
+    }
+}
+
+
+
+pub async fn respond<W: AsyncWrite + Unpin>(writer: &mut W, status: &str, content_type: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body,
+    );
+    writer.write_all(response.as_bytes()).await
+}
+
+pub async fn send_event<W: AsyncWrite + Unpin>(writer: &mut W, message: &SpectatorMessage) -> std::io::Result<()> {
+    let event = format!("data: {}\n\n", serde_json::to_string(message).unwrap_or_default());
+    writer.write_all(event.as_bytes()).await?;
+    writer.flush().await
+}
+
+async fn handle_connection(stream: TcpStream, event_receiver: broadcast::Receiver<Event>, snapshot_receiver: Receiver<Snapshot>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    // =-= Request =-= //
+    // only the request line matters, the headers are read past
+    let Ok(Some(request_line)) = lines.next_line().await else { return };
+    while let Ok(Some(header)) = lines.next_line().await {
+        if header.is_empty() {
+            break;
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default().split('?').next().unwrap_or_default();
+
+    let _ = match (method, path) {
+        ("GET", "/") => respond(&mut writer, "200 OK", "text/html; charset=utf-8", PAGE).await,
+        ("GET", "/snapshot") => {
+            let view = TableView::new(&snapshot_receiver.borrow());
+            respond(&mut writer, "200 OK", "application/json", &serde_json::to_string(&view).unwrap_or_default()).await
+        },
+        ("GET", "/metrics") => {
+            let metrics = snapshot_receiver.borrow().metrics.render();
+            respond(&mut writer, "200 OK", "text/plain; version=0.0.4", &metrics).await
+        },
+        ("GET", "/events") => stream_events(&mut writer, event_receiver, snapshot_receiver).await,
+        ("GET", _) => respond(&mut writer, "404 Not Found", "text/plain", "not found").await,
+        _ => respond(&mut writer, "405 Method Not Allowed", "text/plain", "spectators can only look").await,
+    };
+}
+
+async fn stream_events<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    mut event_receiver: broadcast::Receiver<Event>,
+    mut snapshot_receiver: Receiver<Snapshot>,
+) -> std::io::Result<()> {
+    writer.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n").await?;
+    writer.flush().await?;
+
+    // the reveal comes from the snapshot, the match maker settles the round after `EndRound` goes out
+    let revealed_round = |snapshot: &Snapshot| snapshot.goal_suit.as_ref().map(|_| snapshot.round);
+    let mut last_reveal = revealed_round(&snapshot_receiver.borrow_and_update());
+    let mut keep_alive = tokio::time::interval(tokio::time::Duration::from_secs(KEEP_ALIVE));
+
+    loop {
+        tokio::select! {
+            // events go out before the snapshot that follows them is looked at, so the reveal never overtakes `EndRound`
+            biased;
+            event = event_receiver.recv() => {
+                let message = match event {
+                    Ok(event) => match to_spectator_message(event) {
+                        Some(message) => message,
+                        None => continue,
+                    },
+                    Err(RecvError::Lagged(skipped)) => SpectatorMessage::Lagged { skipped },
+                    Err(RecvError::Closed) => return Ok(()),
+                };
+                send_event(writer, &message).await?;
+            },
+            changed = snapshot_receiver.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+                let reveal = {
+                    let snapshot = snapshot_receiver.borrow_and_update();
+                    let round = revealed_round(&snapshot);
+                    if round.is_some() && round != last_reveal {
+                        last_reveal = round;
+                        Some(TableView::new(&snapshot))
+                    } else {
+                        None
+                    }
+                };
+                if let Some(view) = reveal {
+                    send_event(writer, &SpectatorMessage::Reveal(Box::new(view))).await?;
+                }
+            },
+            _ = keep_alive.tick() => {
+                writer.write_all(b": keep-alive\n\n").await?;
+                writer.flush().await?;
+            },
+        }
+    }
+}
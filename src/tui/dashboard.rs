@@ -0,0 +1,134 @@
+use super::{Screen, book_line, spawn_key_reader, request_quit};
+use crate::{Card, Snapshot, CL};
+use crossterm::event::{KeyCode, KeyModifiers};
+use tokio::sync::watch::Receiver;
+use tracing::error;
+
+
+// =-= Dashboard =-= //
+// - a full-screen spectator view of the match maker, redrawn in place whenever the snapshot changes
+// - hands can be hidden (only card counts are shown) so watching doesn't spoil the goal suit
+// - q leaves the dashboard and ends the game
+
+const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+pub struct Dashboard {
+    pub hide_hands: bool,
+    pub snapshot_receiver: Receiver<Snapshot>,
+    pub last_orders: usize,
+    pub orders_per_second: f64,
+}
+
+impl Dashboard {
+    pub fn new(hide_hands: bool, snapshot_receiver: Receiver<Snapshot>) -> Self {
+        Self {
+            hide_hands,
+            snapshot_receiver,
+            last_orders: 0,
+            orders_per_second: 0.0,
+        }
+    }
+
+
+
+    pub async fn start(&mut self) {
+        let mut key_receiver = spawn_key_reader();
+        let mut screen = match Screen::enter() {
+            Ok(screen) => screen,
+            Err(e) => {
+                error!(error = %e, "unable to open the dashboard");
+                return;
+            }
+        };
+
+        let mut refresh = tokio::time::interval(tokio::time::Duration::from_secs(1)); // order rate is measured per tick
+        loop {
+            tokio::select! {
+                changed = self.snapshot_receiver.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                },
+                Some(key) = key_receiver.recv() => {
+                    let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                    if key.code == KeyCode::Char('q') || ctrl_c {
+                        break;
+                    }
+                },
+                _ = refresh.tick() => {
+                    let orders = self.snapshot_receiver.borrow().orders;
+                    self.orders_per_second = orders.saturating_sub(self.last_orders) as f64;
+                    self.last_orders = orders;
+                },
+            }
+
+            let lines = self.render(&self.snapshot_receiver.borrow());
+            if screen.draw(&lines).is_err() {
+                break;
+            }
+        }
+
+        drop(screen);
+        request_quit();
+    }
+
+
+
+    pub fn render(&self, snapshot: &Snapshot) -> Vec<String> {
+        let seconds_left = snapshot.seconds_left();
+        let clock = if snapshot.trading { format!("{}:{:02}", seconds_left / 60, seconds_left % 60) } else { String::from("-:--") };
+
+        let mut lines = Vec::new();
+        lines.push(format!(
+            "{}|================ Figgie Auto |:| Round {} ================|{}  Time left: {}{}  {}|:| Orders: {} ({:.0}/s) | Queue: {} | Trades: {}",
+            CL::Purple.get(), snapshot.round, CL::Dull.get(), CL::Teal.get(), clock, CL::Dull.get(),
+            snapshot.orders, self.orders_per_second, snapshot.queue, snapshot.trade_count,
+        ));
+        lines.push(String::new());
+
+        for card in SUITS.iter() {
+            let color = match &snapshot.goal_suit {
+                Some(goal_suit) if goal_suit == card => CL::LimeGreen,
+                _ => CL::DullTeal,
+            };
+            if let Some(book) = snapshot.books.get(card) {
+                lines.push(book_line(card, book, &color));
+            }
+        }
+        lines.push(String::new());
+
+        lines.push(format!("{}=---= Players =---={}", CL::DimLightBlue.get(), CL::End.get()));
+        for player_name in &snapshot.player_names {
+            let points = snapshot.points.get(player_name).copied().unwrap_or(0);
+            let inventory = snapshot.inventories.get(player_name).copied().unwrap_or_default();
+            let hand = if self.hide_hands && snapshot.trading {
+                format!("Cards: {}x", inventory.total())
+            } else {
+                format!("Spades: {}x | Clubs: {}x | Diamonds: {}x | Hearts: {}x", inventory.spades, inventory.clubs, inventory.diamonds, inventory.hearts)
+            };
+            let change = match snapshot.point_changes.get(player_name) {
+                Some(change) if *change > 0 => format!("{}(+{})", CL::Green.get(), change),
+                Some(change) if *change < 0 => format!("{}({})", CL::Red.get(), change),
+                _ => String::new(),
+            };
+            let throttled = match snapshot.throttled.get(player_name) {
+                Some(throttled) if *throttled > 0 => format!(" |:| {}Throttled: {}x", CL::Orange.get(), throttled),
+                _ => String::new(),
+            };
+            lines.push(format!("{}{:<15}{} |:| Points: {}{:>4} {}{} |:| {}{}", CL::DimLightBlue.get(), format!("{:?}", player_name), CL::Dull.get(), CL::DullGreen.get(), points, change, CL::Dull.get(), hand, throttled));
+        }
+        lines.push(String::new());
+
+        if let (Some(common_suit), Some(goal_suit)) = (&snapshot.common_suit, &snapshot.goal_suit) {
+            lines.push(format!("{}Last round |:| Common suit: {} | Goal suit: {}{}", CL::Dull.get(), common_suit.plural(), CL::LimeGreen.get(), goal_suit.plural()));
+            lines.push(String::new());
+        }
+
+        lines.push(format!("{}=---= Trade Tape =---={}", CL::DimLightBlue.get(), CL::End.get()));
+        for trade in snapshot.trades.iter().rev().take(12) {
+            lines.push(format!("{} - {:?} @ {} | {:?} -> {:?}", CL::Dull.get(), trade.card, trade.price, trade.seller, trade.buyer));
+        }
+
+        lines
+    }
+}
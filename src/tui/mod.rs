@@ -0,0 +1,93 @@
+use super::{Card, Book, QUIET, CL};
+use super::logging::HELD;
+use crossterm::{cursor, execute, queue};
+use crossterm::event::{self as terminal_event, Event as TerminalEvent, KeyEvent, KeyEventKind};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::sync::Notify;
+use std::io::{self, Stdout, Write};
+use std::sync::atomic::Ordering;
+use std::sync::OnceLock;
+
+pub mod dashboard;
+pub use dashboard::Dashboard;
+
+
+// =-= Screen =-= //
+// - takes over the terminal (raw mode + alternate screen) and silences console! while it's alive
+// - every draw rewrites the screen line by line instead of clearing it first, so nothing flickers
+// - dropping it hands the terminal back, even if we're unwinding from a panic
+
+pub struct Screen {
+    stdout: Stdout,
+    quiet: bool, // whatever console! was doing before, `--log-format json` keeps it quiet after too
+}
+
+impl Screen {
+    pub fn enter() -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        let quiet = QUIET.swap(true, Ordering::Relaxed);
+        HELD.store(true, Ordering::Relaxed);
+        Ok(Self { stdout, quiet })
+    }
+
+    pub fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+        for (row, line) in lines.iter().enumerate() {
+            queue!(self.stdout, cursor::MoveTo(0, row as u16), Print(line), Print(CL::End.get()), Clear(ClearType::UntilNewLine))?;
+        }
+        queue!(self.stdout, cursor::MoveTo(0, lines.len() as u16), Clear(ClearType::FromCursorDown))?;
+        self.stdout.flush()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+        QUIET.store(self.quiet, Ordering::Relaxed);
+        HELD.store(false, Ordering::Relaxed);
+    }
+}
+
+
+// =-= Quitting =-= //
+// - a full-screen view never ends the process itself, it hands the terminal back and asks `main` to wrap up
+// - the request is kept until someone waits on it, so it can't be missed
+
+static QUIT: OnceLock<Notify> = OnceLock::new();
+
+pub fn request_quit() {
+    QUIT.get_or_init(Notify::new).notify_one();
+}
+
+pub async fn quit_requested() {
+    QUIT.get_or_init(Notify::new).notified().await;
+}
+
+
+pub fn spawn_key_reader() -> UnboundedReceiver<KeyEvent> {
+    // crossterm's reader blocks, so it gets its own thread and hands keys over a channel
+    let (key_sender, key_receiver) = mpsc::unbounded_channel::<KeyEvent>();
+    std::thread::spawn(move || loop {
+        if let Ok(TerminalEvent::Key(key)) = terminal_event::read() {
+            if key.kind == KeyEventKind::Press && key_sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+    key_receiver
+}
+
+
+pub fn book_line(card: &Card, book: &Book, color: &CL) -> String {
+    format!(
+        "{}{:<9} {}|:| Bid: ({}{:>2}{}, {:?}) | Ask: ({}{:>2}{}, {:?}) |:| Last trade: {}{}{}",
+        color.get(), card.plural(), CL::Dull.get(),
+        CL::Green.get(), book.bid.price, CL::Dull.get(), book.bid.player_name,
+        CL::PeachRed.get(), book.ask.price, CL::Dull.get(), book.ask.player_name,
+        CL::DimLightBlue.get(), book.last_trade.map(|price| price.to_string()).unwrap_or(String::from("-")), CL::Dull.get(),
+    )
+}
@@ -0,0 +1,88 @@
+use super::{Candidate, Evaluator, CL};
+use super::super::player::StrategyParams;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+
+
+// =-= Genetic Search =-= //
+// - starts from the defaults plus random sets, then each generation keeps the best two as they are and breeds the rest:
+// two parents picked by tournament (best of 3 at random), each parameter taken from either parent, then nudged a step or two
+// either way at the mutation rate
+// - every generation plays the same deals, so a set that survives isn't replayed (see the evaluator's cache)
+// - seeded from the tuning seed, so a search can be rerun
+
+pub const ELITES: usize = 2;
+pub const TOURNAMENT: usize = 3;
+
+fn random_params(base: &StrategyParams, names: &[String], rng: &mut StdRng) -> StrategyParams {
+    let mut params = base.clone();
+    for param in base.params().into_iter().filter(|param| names.contains(&param.name)) {
+        if let Some(value) = param.values().choose(rng) {
+            let _ = params.set(&param.name, *value);
+        }
+    }
+    params
+}
+
+fn tournament<'a>(ranked: &'a [Candidate], rng: &mut StdRng) -> &'a Candidate {
+    // ranked best first, so the lowest index drawn wins
+    (0..TOURNAMENT).map(|_| rng.gen_range(0..ranked.len())).min().map(|idx| &ranked[idx]).unwrap_or(&ranked[0])
+}
+
+fn breed(mother: &StrategyParams, father: &StrategyParams, names: &[String], mutation_rate: f64, rng: &mut StdRng) -> StrategyParams {
+    let mut child = mother.clone();
+    let fathers = father.params();
+    for param in mother.params().into_iter().filter(|param| names.contains(&param.name)) {
+        let mut value = match fathers.iter().find(|other| other.name == param.name) {
+            Some(other) if rng.gen_bool(0.5) => other.value,
+            _ => param.value,
+        };
+        if rng.gen_bool(mutation_rate) {
+            let steps = if rng.gen_bool(0.5) { 1 } else { 2 };
+            value += if rng.gen_bool(0.5) { steps * param.step } else { -steps * param.step };
+        }
+        let _ = child.set(&param.name, value);
+    }
+    child
+}
+
+pub fn genetic_search(evaluator: &mut Evaluator, base: &StrategyParams, names: &[String], seeds: &[u64], seed: u64) -> Vec<Candidate> {
+    let config = evaluator.config;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let population_size = config.population.max(ELITES + 1);
+    console!(
+        "{}[+] Genetic search over {} parameters |:| Population: {} | Generations: {} | {} games each{}",
+        CL::DimLightBlue.get(), names.len(), population_size, config.generations, seeds.len(), CL::End.get(),
+    );
+
+    let mut population: Vec<StrategyParams> = vec![base.clone()];
+    while population.len() < population_size {
+        population.push(random_params(base, names, &mut rng));
+    }
+
+    let mut tried: Vec<Candidate> = Vec::new();
+    for generation in 0..config.generations.max(1) {
+        let mut ranked: Vec<Candidate> = population.iter().map(|params| evaluator.evaluate(params, seeds)).collect();
+        ranked.sort_by(|a, b| b.estimate().mean.total_cmp(&a.estimate().mean));
+        let means: Vec<f64> = ranked.iter().map(|candidate| candidate.estimate().mean).collect();
+        console!(
+            "{}[+] Generation {}/{} |:| Best: {:+.1} | Median: {:+.1} | Worst: {:+.1}{}",
+            CL::DullGreen.get(), generation + 1, config.generations.max(1), means[0], means[means.len() / 2], means[means.len() - 1], CL::End.get(),
+        );
+
+        for candidate in ranked.iter() {
+            if !tried.iter().any(|other| other.params == candidate.params) {
+                tried.push(candidate.clone());
+            }
+        }
+
+        population = ranked.iter().take(ELITES).map(|candidate| candidate.params.clone()).collect();
+        while population.len() < population_size {
+            let mother = tournament(&ranked, &mut rng);
+            let father = tournament(&ranked, &mut rng);
+            population.push(breed(&mother.params, &father.params, names, config.mutation_rate, &mut rng));
+        }
+    }
+    tried
+}
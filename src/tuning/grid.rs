@@ -0,0 +1,58 @@
+use super::{Candidate, Evaluator, CL};
+use super::super::player::StrategyParams;
+use std::collections::HashSet;
+
+
+// =-= Grid Search =-= //
+// - every combination of the chosen parameters' values, each from its min to its max in steps (see `Param`)
+// - combinations that come out the same once normalised (e.g. an empty frequency range) are only played once
+
+pub fn grid(base: &StrategyParams, names: &[String]) -> Vec<StrategyParams> {
+    let mut grid = vec![base.clone()];
+    for name in names.iter() {
+        let values = base.params().into_iter().find(|param| param.name == *name).map(|param| param.values()).unwrap_or_default();
+        let mut next = Vec::with_capacity(grid.len() * values.len());
+        for params in grid.iter() {
+            for value in values.iter() {
+                let mut params = params.clone();
+                if params.set(name, *value).is_ok() {
+                    next.push(params);
+                }
+            }
+        }
+        grid = next;
+    }
+
+    let mut seen = HashSet::new();
+    grid.retain(|params| seen.insert(params.summary()));
+    grid
+}
+
+pub fn grid_size(base: &StrategyParams, names: &[String]) -> usize {
+    // before duplicates are dropped, so it can be checked without building the grid
+    names.iter()
+        .map(|name| base.params().into_iter().find(|param| param.name == *name).map(|param| param.values().len()).unwrap_or(1))
+        .fold(1usize, |size, values| size.saturating_mul(values))
+}
+
+pub fn grid_search(evaluator: &mut Evaluator, base: &StrategyParams, names: &[String], seeds: &[u64]) -> Result<Vec<Candidate>, String> {
+    let size = grid_size(base, names);
+    if size > evaluator.config.max_grid {
+        return Err(format!("a grid over {} is {} candidates, pick fewer parameters with --tune-params or use --search genetic", names.join(", "), size));
+    }
+
+    let grid = grid(base, names);
+    console!("{}[+] Grid search over {} candidates, {} games each{}", CL::DimLightBlue.get(), grid.len(), seeds.len(), CL::End.get());
+    let mut best: Option<f64> = None;
+    let mut candidates = Vec::with_capacity(grid.len());
+    for (idx, params) in grid.iter().enumerate() {
+        let candidate = evaluator.evaluate(params, seeds);
+        let mean = candidate.estimate().mean;
+        if best.is_none_or(|best| mean > best) {
+            best = Some(mean);
+            console!("{}[+] {}/{} |:| New best {:+.1} per game{}", CL::DullGreen.get(), idx + 1, grid.len(), mean, CL::End.get());
+        }
+        candidates.push(candidate);
+    }
+    Ok(candidates)
+}
@@ -0,0 +1,254 @@
+use super::{PlayerName, QUIET, CL};
+use super::batch::{BatchConfig, play_game};
+use super::player::StrategyParams;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+
+pub mod grid;
+pub use grid::*;
+
+pub mod genetic;
+pub use genetic::*;
+
+
+// =-= Tuning =-= //
+// - searches one strategy's parameters (see `player/params.rs`) by playing headless games (see `batch/mod.rs`) against a fixed field
+// - a candidate's fitness is the average points the tuned player makes per game, every candidate plays the same seeded deals
+// so they're compared on the same cards (the bots' own dice are still their own)
+// - `grid.rs` tries every combination of the chosen parameters, `genetic.rs` evolves a population of them for when the grid is too big
+// - the best of a search is lucky as well as good, so it's replayed against the defaults on deals it hasn't seen,
+// and the difference is reported with a 95% confidence interval
+
+pub const Z_95: f64 = 1.96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Search {
+    Grid,
+    Genetic,
+}
+
+impl FromStr for Search {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "grid" => Ok(Search::Grid),
+            "genetic" => Ok(Search::Genetic),
+            _ => Err(format!("unknown '{}', pick from grid or genetic", text)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TuneConfig {
+    pub batch: BatchConfig, // the field (tuned player included), games per candidate, rounds per game and the seed
+    pub player: PlayerName,
+    pub params: Vec<String>, // which parameters to search, the rest stay at their defaults
+    pub search: Search,
+    pub population: usize,
+    pub generations: usize,
+    pub mutation_rate: f64,
+    pub validation_games: usize,
+    pub max_grid: usize, // refuse grids bigger than this, use the genetic search instead
+    pub top: usize,
+    pub output: Option<PathBuf>,
+}
+
+impl TuneConfig {
+    pub fn new(batch: BatchConfig, player: PlayerName) -> Self {
+        Self {
+            batch,
+            player,
+            params: Vec::new(),
+            search: Search::Grid,
+            population: 12,
+            generations: 8,
+            mutation_rate: 0.25,
+            validation_games: 20,
+            max_grid: 500,
+            top: 5,
+            output: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub std_error: f64,
+}
+
+impl Estimate {
+    pub fn new(samples: &[f64]) -> Self {
+        let n = samples.len() as f64;
+        if samples.is_empty() {
+            return Self { mean: 0.0, std_error: 0.0 };
+        }
+        let mean = samples.iter().sum::<f64>() / n;
+        if samples.len() < 2 {
+            return Self { mean, std_error: 0.0 };
+        }
+        let variance = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Self { mean, std_error: (variance / n).sqrt() }
+    }
+
+    pub fn interval(&self) -> (f64, f64) {
+        (self.mean - Z_95 * self.std_error, self.mean + Z_95 * self.std_error)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub params: StrategyParams,
+    pub pnls: Vec<i64>, // one per game, in seed order
+}
+
+impl Candidate {
+    pub fn estimate(&self) -> Estimate {
+        Estimate::new(&self.pnls.iter().map(|pnl| *pnl as f64).collect::<Vec<_>>())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TuneResult {
+    pub best: Candidate,
+    pub default: Candidate, // both replayed on the validation deals
+    pub ranked: Vec<Candidate>, // everything the search tried, best first, on the search deals
+    pub improvement: Estimate, // best minus default, deal by deal
+}
+
+pub struct Evaluator<'a> {
+    pub config: &'a TuneConfig,
+    pub cache: HashMap<String, Candidate>, // the genetic search keeps running into the same sets
+    pub games: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(config: &'a TuneConfig) -> Self {
+        Self { config, cache: HashMap::new(), games: 0 }
+    }
+
+    pub fn evaluate(&mut self, params: &StrategyParams, seeds: &[u64]) -> Candidate {
+        let key = format!("{} {:?}", params.summary(), seeds);
+        if let Some(candidate) = self.cache.get(&key) {
+            return candidate.clone();
+        }
+
+        let mut batch = self.config.batch.clone();
+        batch.params.insert(self.config.player.clone(), params.clone());
+        let quiet = QUIET.load(Ordering::Relaxed);
+        let mut pnls = Vec::new();
+        for seed in seeds.iter() {
+            QUIET.store(true, Ordering::Relaxed);
+            let result = play_game(&batch, self.games, &batch.lineup, Some(*seed));
+            QUIET.store(quiet, Ordering::Relaxed);
+            self.games += 1;
+
+            let points = result.points.iter().find(|(player_name, _)| *player_name == self.config.player).map(|(_, points)| *points).unwrap_or(0);
+            pnls.push(points as i64 - batch.starting_balance as i64);
+        }
+
+        let candidate = Candidate { params: params.clone(), pnls };
+        self.cache.insert(key, candidate.clone());
+        candidate
+    }
+}
+
+
+pub fn run_tuning(config: &TuneConfig) -> Result<TuneResult, String> {
+    let default = StrategyParams::for_player(&config.player).ok_or_else(|| format!("{:?} has no parameters to tune", config.player))?;
+    if !config.batch.lineup.contains(&config.player) {
+        return Err(format!("{:?} isn't in the lineup", config.player));
+    }
+    let names: Vec<String> = if config.params.is_empty() {
+        default.params().into_iter().map(|param| param.name).collect()
+    } else {
+        config.params.clone()
+    };
+    for name in names.iter() {
+        default.clone().set(name, 0)?; // just checking the name
+    }
+
+    let base_seed = config.batch.seed.unwrap_or_else(rand::random::<u64>);
+    if config.batch.seed.is_none() {
+        console!("{}[+] Tuning on deals from seed {} (pass --seed {} to replay them){}", CL::DimLightBlue.get(), base_seed, base_seed, CL::End.get());
+    }
+    let seeds: Vec<u64> = (0..config.batch.games).map(|game| base_seed.wrapping_add(game as u64)).collect();
+    let validation_seeds: Vec<u64> = (0..config.validation_games).map(|game| base_seed.wrapping_add((config.batch.games + game) as u64)).collect();
+
+    let mut evaluator = Evaluator::new(config);
+    let mut ranked = match config.search {
+        Search::Grid => grid_search(&mut evaluator, &default, &names, &seeds)?,
+        Search::Genetic => genetic_search(&mut evaluator, &default, &names, &seeds, base_seed),
+    };
+    ranked.sort_by(|a, b| b.estimate().mean.total_cmp(&a.estimate().mean));
+
+    console!();
+    narrate!(
+        info!(player = %config.player, candidates = ranked.len(), games = seeds.len(), seed = base_seed, "search done"),
+        "=---= Top {} of {} ({} games each) =---=", config.top.min(ranked.len()), ranked.len(), seeds.len()
+    );
+    for candidate in ranked.iter().take(config.top) {
+        print_candidate(candidate, &names);
+    }
+
+    // replay the winner and the defaults on fresh deals, the search picked the winner partly for its luck on the old ones
+    let best = evaluator.evaluate(&ranked[0].params, &validation_seeds);
+    let baseline = evaluator.evaluate(&default, &validation_seeds);
+    let differences: Vec<f64> = best.pnls.iter().zip(baseline.pnls.iter()).map(|(best, baseline)| (best - baseline) as f64).collect();
+    let improvement = Estimate::new(&differences);
+    let (low, high) = improvement.interval();
+
+    console!();
+    console!("=---= Validation ({} fresh deals) =---=", validation_seeds.len());
+    console!("{}Best{}     |:| {}", CL::LimeGreen.get(), CL::End.get(), format_estimate(&best.estimate()));
+    console!("{}Defaults{} |:| {}", CL::Dull.get(), CL::End.get(), format_estimate(&baseline.estimate()));
+    let verdict = if low > 0.0 {
+        format!("{}better than the defaults with 95% confidence{}", CL::Green.get(), CL::End.get())
+    } else if high < 0.0 {
+        format!("{}worse than the defaults with 95% confidence{}", CL::Red.get(), CL::End.get())
+    } else {
+        format!("{}can't be told apart from the defaults, try more games{}", CL::Orange.get(), CL::End.get())
+    };
+    narrate!(
+        info!(
+            player = %config.player, best = best.estimate().mean, defaults = baseline.estimate().mean,
+            difference = improvement.mean, low, high, games = evaluator.games, params = %best.params.label(), "validated",
+        ),
+        "Difference |:| {:+.1} per game (95% CI {:+.1} to {:+.1}) |:| {}", improvement.mean, low, high, verdict
+    );
+    console!("{}[+] {} games played{}", CL::Dull.get(), evaluator.games, CL::End.get());
+    console!();
+    console!("{}", serde_json::to_string_pretty(&best.params).unwrap_or_default());
+
+    if let Some(path) = &config.output {
+        let text = serde_json::to_string_pretty(&best.params).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("unable to write {:?}: {}", path, e))?;
+        narrate!(
+            info!(path = ?path, "saved the best parameters"),
+            "{}[+] Saved the best parameters to {:?}{}", CL::DimLightBlue.get(), path, CL::End.get()
+        );
+    }
+
+    Ok(TuneResult { best, default: baseline, ranked, improvement })
+}
+
+pub fn format_estimate(estimate: &Estimate) -> String {
+    let (low, high) = estimate.interval();
+    format!("{:+.1} per game (95% CI {:+.1} to {:+.1})", estimate.mean, low, high)
+}
+
+pub fn print_candidate(candidate: &Candidate, names: &[String]) {
+    let params: Vec<String> = candidate.params.params().into_iter()
+        .filter(|param| names.contains(&param.name))
+        .map(|param| format!("{}={}", param.name, param.value))
+        .collect();
+    let estimate = candidate.estimate();
+    let (low, high) = estimate.interval();
+    narrate!(
+        info!(params = %params.join(" "), mean = estimate.mean, low, high, "candidate"),
+        "{}{}{} |:| {}{}", CL::DimLightBlue.get(), params.join(" "), CL::Dull.get(), format_estimate(&candidate.estimate()), CL::End.get()
+    );
+}
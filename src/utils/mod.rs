@@ -1,5 +1,36 @@
 use std::fs::OpenOptions;
 use std::io::{self, Write};
+use std::sync::atomic::AtomicBool;
+
+
+// =-= Console =-= //
+// - everything that used to go straight to println! goes through console! instead,
+// so a full-screen view (like the human seat) can take over the terminal without being scribbled on
+pub static QUIET: AtomicBool = AtomicBool::new(false);
+pub static NO_COLOR: AtomicBool = AtomicBool::new(false); // `--no-color`, every `CL` comes out empty
+
+#[macro_export]
+macro_rules! console {
+    ($($arg:tt)*) => {
+        if !$crate::utils::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
+            println!($($arg)*);
+        }
+    };
+}
+
+// - the game's commentary (books, hands, results, reports, standings) goes through narrate! instead, one call per line:
+// printed for people while console! is showing, otherwise a `tracing` event marked `narrative` with the same facts as fields
+// (e.g. `--log-format json`, a batch game, or while a full-screen view has the terminal)
+#[macro_export]
+macro_rules! narrate {
+    ($level:ident!($($event:tt)*), $($arg:tt)*) => {
+        if $crate::utils::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
+            ::tracing::$level!(narrative = true, $($event)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
 
 
 #[derive(Debug, Clone)]
@@ -23,6 +54,9 @@ pub enum CL {
 
 impl CL {
     pub fn get(&self) -> &str {
+        if NO_COLOR.load(std::sync::atomic::Ordering::Relaxed) {
+            return "";
+        }
         match self {
             CL::Pink => "\x1b[38;5;165m",
             CL::Purple => "\x1b[38;5;135m",
@@ -52,7 +86,6 @@ impl FileHandler {
     pub fn new(file_path: &str) -> io::Result<Self> {
         let file = OpenOptions::new()
             .create(true)
-            .write(true)
             .append(true)
             .open(file_path)?;
         Ok(Self { file })
@@ -0,0 +1,120 @@
+mod common;
+
+use common::*;
+use figgie_auto::analytics::{SessionReport, markouts, TRADE_HORIZONS};
+use figgie_auto::{Card, Direction, PlayerName, Trade};
+use proptest::prelude::*;
+
+
+// =-= Analytics =-= //
+// - the PnL breakdown has to add back up to what actually happened to everyone's points
+// - markouts are marked from the maker's side, against later trades in the same suit and the settlement value
+
+fn trade(card: Card, price: usize, maker: &str, taker: &str, aggressor: Direction) -> Trade {
+    let (maker, taker) = (PlayerName::from(maker.to_string()), PlayerName::from(taker.to_string()));
+    let (buyer, seller) = match aggressor {
+        Direction::Buy => (taker, maker),
+        Direction::Sell => (maker, taker),
+    };
+    Trade { card, price, buyer, seller, seq: 0, resting_seq: 0, aggressor, timestamp: 0 }
+}
+
+#[test]
+fn markouts_are_taken_from_the_makers_side() {
+    // Spread sells a goal card at 6 to PickOff, a heart trades at 2, then Spread buys a spade back from Seller at 8
+    let trades = vec![
+        trade(Card::Spade, 6, "Spread", "PickOff", Direction::Buy),
+        trade(Card::Heart, 2, "Noisy", "Seller", Direction::Sell),
+        trade(Card::Spade, 8, "Spread", "Seller", Direction::Sell),
+    ];
+    let markouts = markouts(&trades, &Card::Spade);
+    assert_eq!(markouts.len(), 3);
+
+    let picked_off = &markouts[0];
+    assert_eq!((&picked_off.maker, &picked_off.taker), (&PlayerName::Spread, &PlayerName::PickOff));
+    assert_eq!(picked_off.after[0].total, 6 - 8); // sold at 6, next spade went at 8
+    assert_eq!(picked_off.after[1].fills, 0); // there's no third spade trade to mark against
+    assert_eq!(picked_off.close.total, 6 - 8);
+    assert_eq!(picked_off.settlement.total, 6 - 10);
+
+    let hearts = &markouts[1];
+    assert_eq!(hearts.after[0].fills, 0);
+    assert_eq!(hearts.close.fills, 0);
+    assert_eq!(hearts.settlement.total, 0 - 2); // bought a worthless heart at 2
+
+    let bought_back = &markouts[2];
+    assert_eq!((&bought_back.maker, &bought_back.taker), (&PlayerName::Spread, &PlayerName::Seller));
+    assert_eq!(bought_back.settlement.total, 10 - 8);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    fn pnl_attribution_adds_up(
+        seed in any::<u64>(),
+        players in 3..=6usize,
+        stp in self_trade_prevention(),
+        rounds in prop::collection::vec(prop::collection::vec(action(), 0..150), 1..4),
+    ) {
+        let mut test_engine = engine(seed, players, stp);
+        let engine = &mut test_engine.engine;
+        let mut session = SessionReport::default();
+        let start = engine.player_points.clone();
+
+        for actions in rounds.iter() {
+            let before = engine.player_points.clone();
+            engine.round_trades.clear();
+            engine.collect_ante(200 / players);
+            let pot = engine.pot;
+            engine.deal();
+            engine.pull_uncovered_quotes();
+            for action in actions.iter() {
+                step(engine, action);
+            }
+            engine.settle_round();
+
+            let report = engine.round_report();
+            prop_assert_eq!(report.players.len(), players);
+            for player in report.players.iter() {
+                let change = engine.player_points[&player.player_name] as i64 - before[&player.player_name] as i64;
+                prop_assert_eq!(player.pnl, change, "{:?}", player);
+                prop_assert_eq!(player.pnl, player.ante + player.trading + player.goal_payout + player.bonus);
+            }
+
+            // trading only moves points between players, the ante all goes into the pot and the pot all comes back out
+            prop_assert_eq!(report.players.iter().map(|player| player.trading).sum::<i64>(), 0);
+            prop_assert_eq!(report.players.iter().map(|player| player.ante).sum::<i64>(), -(pot as i64));
+            prop_assert_eq!(report.players.iter().map(|player| player.goal_payout + player.bonus).sum::<i64>(), pot as i64);
+
+            let bought: usize = report.players.iter().flat_map(|player| player.suits.values()).map(|stats| stats.bought).sum();
+            let sold: usize = report.players.iter().flat_map(|player| player.suits.values()).map(|stats| stats.sold).sum();
+            prop_assert_eq!(bought, report.trades);
+            prop_assert_eq!(sold, report.trades);
+
+            // every fill between two different players is marked exactly once, and never against more trades than it saw
+            let fills: usize = report.markouts.iter().map(|pair| pair.fills).sum();
+            let self_trades = engine.round_trades.iter().filter(|trade| trade.buyer == trade.seller).count();
+            prop_assert_eq!(fills + self_trades, report.trades);
+            for pair in report.markouts.iter() {
+                prop_assert!(pair.maker != pair.taker);
+                prop_assert_eq!(pair.settlement.fills, pair.fills);
+                prop_assert!(pair.close.fills <= pair.fills);
+                prop_assert_eq!(pair.after.len(), TRADE_HORIZONS.len());
+                for window in pair.after.windows(2) {
+                    prop_assert!(window[1].fills <= window[0].fills);
+                }
+            }
+
+            session.absorb(&report);
+        }
+
+        prop_assert_eq!(session.rounds, rounds.len());
+        for player in session.players.iter() {
+            let change = engine.player_points[&player.player_name] as i64 - start[&player.player_name] as i64;
+            prop_assert_eq!(player.pnl, change);
+        }
+        let fills: usize = session.markouts.iter().map(|pair| pair.fills).sum();
+        prop_assert!(fills <= session.trades);
+    }
+}
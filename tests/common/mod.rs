@@ -0,0 +1,108 @@
+// shared by every test file, not every file uses every helper
+#![allow(dead_code)]
+
+use figgie_auto::{MatchMaker, SelfTradePrevention, Card, Book, Order, Cancel, Direction, Request, Trade, Event, PlayerName, QUIET};
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tokio::sync::broadcast;
+
+
+// =-= Test Engine =-= //
+// - a match maker with no players attached, requests are fed straight into `process` so nothing depends on timing
+// - the event receiver is kept around so sends don't fail, nobody reads from it
+// - seeded, so a failing case replays exactly
+
+pub const STARTING_BALANCE: usize = 500;
+pub const SUITS: [Card; 4] = [Card::Spade, Card::Club, Card::Diamond, Card::Heart];
+
+pub struct TestEngine {
+    pub engine: MatchMaker,
+    _events: broadcast::Receiver<Event>,
+}
+
+pub fn engine(seed: u64, players: usize, self_trade_prevention: SelfTradePrevention) -> TestEngine {
+    QUIET.store(true, Ordering::Relaxed);
+
+    let player_names: Vec<PlayerName> = (0..players).map(|idx| PlayerName::External(format!("p{}", idx))).collect();
+    let (event_sender, events) = broadcast::channel::<Event>(100);
+    let (_, order_receiver) = kanal::unbounded_async::<Request>();
+
+    let mut engine = MatchMaker::new(STARTING_BALANCE, player_names, event_sender, Arc::new(order_receiver));
+    engine.seed(seed);
+    engine.self_trade_prevention = self_trade_prevention;
+    TestEngine { engine, _events: events }
+}
+
+pub fn dealt_engine(seed: u64, players: usize, self_trade_prevention: SelfTradePrevention) -> TestEngine {
+    let mut test_engine = engine(seed, players, self_trade_prevention);
+    let ante = 200 / players;
+    test_engine.engine.collect_ante(ante);
+    test_engine.engine.deal();
+    test_engine
+}
+
+
+// =-= Strategies =-= //
+
+#[derive(Debug, Clone)]
+pub struct Action {
+    pub player: usize, // taken modulo the number of seats
+    pub card: usize,
+    pub buy: bool,
+    pub price: usize,
+    pub cancel: bool,
+}
+
+pub fn action() -> impl Strategy<Value = Action> {
+    // prices sit around where the bots trade, with the odd zero to make sure those are turned away
+    (0..8usize, 0..4usize, any::<bool>(), 0..=25usize, prop::bool::weighted(0.15))
+        .prop_map(|(player, card, buy, price, cancel)| Action { player, card, buy, price, cancel })
+}
+
+pub fn self_trade_prevention() -> impl Strategy<Value = SelfTradePrevention> {
+    prop_oneof![
+        Just(SelfTradePrevention::Allow),
+        Just(SelfTradePrevention::Reject),
+        Just(SelfTradePrevention::CancelIncoming),
+        Just(SelfTradePrevention::CancelResting),
+    ]
+}
+
+
+// =-= Stepping =-= //
+
+pub struct Step {
+    pub request: Request,
+    pub before: HashMap<Card, Book>,
+    pub valid: bool, // would the order have passed `validate_order`
+    pub trade: Option<Trade>,
+}
+
+pub fn request(engine: &MatchMaker, action: &Action) -> Request {
+    let player_name = engine.player_names[action.player % engine.player_names.len()].clone();
+    let card = SUITS[action.card].clone();
+    let direction = if action.buy { Direction::Buy } else { Direction::Sell };
+    if action.cancel {
+        Request::Cancel(Cancel { player_name, direction, card })
+    } else {
+        Request::Order(Order { player_name, price: action.price, direction, card })
+    }
+}
+
+pub fn step(engine: &mut MatchMaker, action: &Action) -> Step {
+    let request = request(engine, action);
+    let before = engine.books.clone();
+    let valid = match &request {
+        Request::Order(order) => order.price > 0 && engine.validate_order(order).is_ok(),
+        Request::Cancel(_) | Request::Disconnect(_) => false,
+    };
+    let sequenced = engine.stamp(request.clone());
+    let trade = engine.process(sequenced);
+    Step { request, before, valid, trade }
+}
+
+pub fn quoted(book: &Book) -> (bool, bool) {
+    (book.bid.player_name != PlayerName::None, book.ask.player_name != PlayerName::None)
+}
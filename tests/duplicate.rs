@@ -0,0 +1,77 @@
+use figgie_auto::analytics::SessionReport;
+use figgie_auto::batch::{DealResult, GameResult};
+use figgie_auto::PlayerName;
+use proptest::prelude::*;
+
+
+// =-= Duplicate =-= //
+// - seat-adjusted scores only move points between the players who sat the deal, they add up to nothing
+// - a player who makes exactly what everyone else made in each seat scores nothing
+
+const STARTING_BALANCE: usize = 500;
+
+fn lineup(seats: usize) -> Vec<PlayerName> {
+    (0..seats).map(|seat| PlayerName::from(format!("p{}", seat))).collect()
+}
+
+fn game(game: usize, lineup: &[PlayerName], points: &[usize]) -> GameResult {
+    GameResult {
+        game,
+        seed: Some(0),
+        points: lineup.iter().cloned().zip(points.iter().copied()).collect(),
+        rounds: Vec::new(),
+        session: SessionReport::default(),
+    }
+}
+
+fn deal(seats: usize, points: &[Vec<usize>]) -> DealResult {
+    let lineup = lineup(seats);
+    let games = points.iter().enumerate()
+        .map(|(rotation, points)| {
+            let mut rotated = lineup.clone();
+            rotated.rotate_right(rotation);
+            game(rotation, &rotated, points)
+        })
+        .collect();
+    DealResult::new(0, 0, &lineup, games, STARTING_BALANCE)
+}
+
+fn rotations() -> impl Strategy<Value = (usize, Vec<Vec<usize>>)> {
+    (4..=5usize).prop_flat_map(|seats| {
+        (Just(seats), prop::collection::vec(prop::collection::vec(300..=700usize, seats), seats))
+    })
+}
+
+#[test]
+fn even_players_score_nothing() {
+    // whoever sits seat 0 makes 40, everyone else loses 10
+    let points: Vec<Vec<usize>> = (0..4).map(|_| vec![540, 490, 490, 490]).collect();
+    let result = deal(4, &points);
+    assert_eq!(result.seat_values, vec![40.0, -10.0, -10.0, -10.0]);
+    for score in result.scores.iter() {
+        assert_eq!(score.adjusted, 0.0, "{:?}", score);
+        assert_eq!(score.seats.iter().sum::<i64>(), 10);
+    }
+}
+
+proptest! {
+    #[test]
+    fn seat_adjusted_scores_are_zero_sum((seats, points) in rotations()) {
+        let result = deal(seats, &points);
+        prop_assert_eq!(result.scores.len(), seats);
+        let total: f64 = result.scores.iter().map(|score| score.adjusted).sum();
+        prop_assert!(total.abs() < 1e-6, "{}", total);
+
+        // every player sat every seat exactly once
+        for score in result.scores.iter() {
+            let raw: i64 = score.seats.iter().sum();
+            let expected: i64 = points.iter().enumerate()
+                .map(|(rotation, points)| {
+                    let player = result.scores.iter().position(|other| other.player_name == score.player_name).unwrap();
+                    points[(player + rotation) % seats] as i64 - STARTING_BALANCE as i64
+                })
+                .sum();
+            prop_assert_eq!(raw, expected);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use figgie_auto::{deck_probabilities, FairValue, Inventory, SelfTradePrevention};
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+
+// =-= Fair Value =-= //
+// - with nothing seen, the decks are as likely as the match maker makes them
+// - seeing cards only ever rules decks out, never in, and the odds always add up to one
+// - quotes stay on the book's 0..99 range with the bid under the ask, whatever the hand
+
+fn inventory(counts: [usize; 4]) -> Inventory {
+    let mut inventory = Inventory::new();
+    for (card, count) in SUITS.iter().zip(counts) {
+        for _ in 0..count {
+            inventory.change(card.clone(), true).unwrap();
+        }
+    }
+    inventory
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn posteriors_rule_out_impossible_decks(seen in [0..=12usize, 0..=12usize, 0..=12usize, 0..=12usize]) {
+        let seen = inventory(seen);
+        let decks = deck_probabilities(&seen);
+        prop_assert_eq!(decks.len(), 12);
+        prop_assert!((decks.iter().map(|deck| deck.probability).sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let possible = |deck: &figgie_auto::Deck| SUITS.iter().all(|card| deck.count(card) >= seen.get(card));
+        if decks.iter().any(possible) {
+            for deck in decks.iter().filter(|deck| !possible(deck)) {
+                prop_assert_eq!(deck.probability, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn quotes_stay_on_the_book(
+        held in [0..=12usize, 0..=12usize, 0..=12usize, 0..=12usize],
+        seen in [0..=12usize, 0..=12usize, 0..=12usize, 0..=12usize],
+        rivals in [0.0..12.0f64, 0.0..12.0f64, 0.0..12.0f64, 0.0..12.0f64],
+        pot in 0..=400usize,
+        edge in -200.0..200.0f64,
+    ) {
+        let rivals: HashMap<_, _> = SUITS.iter().cloned().zip(rivals).collect();
+        let fair_value = FairValue::new(inventory(seen), inventory(held), rivals, pot);
+        for card in SUITS.iter() {
+            let (bid, ask) = fair_value.quote(card, edge);
+            prop_assert!(bid <= 98, "bid {}", bid);
+            prop_assert!((1..=99).contains(&ask), "ask {}", ask);
+            prop_assert!(bid < ask);
+        }
+    }
+}
+
+#[test]
+fn prior_matches_the_deal() {
+    let mut test_engine = engine(7, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    let deals = 8000;
+    let mut counted: HashMap<(String, String), usize> = HashMap::new();
+    for _ in 0..deals {
+        let starting_inventory = engine.deal();
+        let eight_suit = SUITS.iter().find(|card| starting_inventory[card] == 8).unwrap();
+        *counted.entry((format!("{:?}", engine.common_suit), format!("{:?}", eight_suit))).or_default() += 1;
+    }
+
+    for deck in deck_probabilities(&Inventory::new()) {
+        let seen = counted.get(&(format!("{:?}", deck.common_suit), format!("{:?}", deck.eight_suit))).copied().unwrap_or(0);
+        let frequency = seen as f64 / deals as f64;
+        assert!((frequency - deck.probability).abs() < 0.02, "{:?} dealt {:.3} of the time, expected {:.3}", deck, frequency, deck.probability);
+    }
+}
+
+#[test]
+fn seeing_cards_moves_the_odds() {
+    // 11 spades can only come from a deck where spades are common, which makes clubs the goal
+    let fair_value = FairValue::new(inventory([11, 0, 0, 0]), Inventory::new(), HashMap::new(), 200);
+    assert!((fair_value.goal_probability(&SUITS[1]) - 1.0).abs() < 1e-9);
+    assert_eq!(fair_value.goal_probability(&SUITS[0]), 0.0);
+
+    // 9 hearts rules hearts out of being the short suit
+    let decks = deck_probabilities(&inventory([0, 0, 0, 9]));
+    assert!(decks.iter().filter(|deck| deck.eight_suit == SUITS[3]).all(|deck| deck.probability == 0.0));
+
+    // with nothing seen, every suit is as likely to be the goal as it is to be common
+    let fair_value = FairValue::new(Inventory::new(), Inventory::new(), HashMap::new(), 200);
+    for card in SUITS.iter() {
+        assert!((fair_value.goal_probability(card) - 0.25).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use figgie_auto::gateway::{current_deal, ServerMessage};
+use figgie_auto::{Card, Direction, Order, PlayerName, Request, SelfTradePrevention, ThrottleAction, ThrottleConfig};
+
+
+// =-= Gateway =-= //
+// - a bot that connects after the deal is dealt what its seat holds right then, and nothing between rounds
+// - once a bot is gone its quotes come off the book straight away, even with its orders stuck behind the throttle
+
+#[test]
+fn late_bots_get_their_seats_hand() {
+    let mut test_engine = engine(9, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    let seat = PlayerName::External(String::from("p2"));
+    assert!(current_deal(&engine.snapshot_sender.borrow(), &seat).is_none());
+
+    engine.collect_ante(50);
+    engine.deal();
+    let hand_of = |message: Option<ServerMessage>| match message {
+        Some(ServerMessage::Deal { hand, players }) => {
+            assert_eq!(players.len(), 4);
+            Some(hand)
+        },
+        _ => None,
+    };
+
+    // dealt but not trading yet, then mid-round
+    engine.publish_snapshot();
+    assert_eq!(hand_of(current_deal(&engine.snapshot_sender.borrow(), &seat)), engine.player_inventories.get(&seat).copied());
+    engine.trading = true;
+    engine.round_start = Some(tokio::time::Instant::now());
+    engine.publish_snapshot();
+    assert_eq!(hand_of(current_deal(&engine.snapshot_sender.borrow(), &seat)), engine.player_inventories.get(&seat).copied());
+    assert!(current_deal(&engine.snapshot_sender.borrow(), &PlayerName::External(String::from("nobody"))).is_none());
+
+    engine.trading = false;
+    engine.settle_round();
+    engine.publish_snapshot();
+    assert!(current_deal(&engine.snapshot_sender.borrow(), &seat).is_none());
+}
+
+#[test]
+fn disconnects_skip_the_throttle() {
+    let mut test_engine = dealt_engine(4, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    engine.trading = true;
+    let seat = engine.player_names[1].clone();
+    for card in SUITS.iter() {
+        let order = Order { player_name: seat.clone(), price: 3, direction: Direction::Buy, card: card.clone() };
+        let sequenced = engine.stamp(Request::Order(order));
+        engine.process(sequenced);
+    }
+    assert!(engine.books.values().any(|book| book.bid.player_name == seat));
+
+    // nothing more gets through for the seat, and only one request of theirs can wait
+    engine.throttle.config = ThrottleConfig { orders_per_second: 0.0, burst: 0.0, max_outstanding: 1, action: ThrottleAction::Delay };
+    engine.admit(Request::Order(Order { player_name: seat.clone(), price: 4, direction: Direction::Buy, card: Card::Club }));
+    assert_eq!(engine.throttle.outstanding(&seat), 1);
+
+    engine.admit(Request::Disconnect(seat.clone()));
+    assert!(engine.books.values().all(|book| book.bid.player_name != seat && book.ask.player_name != seat));
+    assert_eq!(engine.throttle.outstanding(&seat), 0);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::*;
+use figgie_auto::logging::{is_narrative, LogConfig, LogFormat, COMPONENTS};
+use figgie_auto::SelfTradePrevention;
+use proptest::prelude::*;
+use std::sync::{Arc, Mutex};
+use tracing::level_filters::LevelFilter;
+use tracing::{Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+
+// =-= Logging =-= //
+// - a component's own level wins over the default, and the last one given for a component is the one kept
+// - anything that isn't a component, a level or a format is turned away
+// - a game's commentary is logged from the module that plays it, so a component's level covers it
+
+const LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+// a real module under each component, events are targeted at the module path they're logged from
+fn module_paths(component: &str) -> Vec<String> {
+    let nested = match component {
+        "match_maker" => Some("audit"),
+        "player" => Some("generic"),
+        "gateway" => Some("subprocess"),
+        "analytics" => Some("markout"),
+        "batch" => Some("duplicate"),
+        "tuning" => Some("grid"),
+        "tui" => Some("dashboard"),
+        _ => None,
+    };
+    let module = format!("figgie_auto::{}", component);
+    match nested {
+        Some(nested) => vec![format!("{}::{}", module, nested), module],
+        None => vec![module],
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    fn components_override_the_default(default in 0..LEVELS.len(), directives in prop::collection::vec((0..COMPONENTS.len(), 0..LEVELS.len()), 0..8)) {
+        let mut levels = vec![LEVELS[default].to_string()];
+        levels.extend(directives.iter().map(|(component, level)| format!("{}={}", COMPONENTS[*component], LEVELS[*level])));
+        let mut config = LogConfig::new(LevelFilter::INFO);
+        prop_assert!(config.parse_levels(&levels.join(",")).is_ok());
+        prop_assert!(config.components.len() <= COMPONENTS.len());
+
+        let targets = config.targets();
+        for (idx, component) in COMPONENTS.iter().enumerate() {
+            let level = directives.iter().rev().find(|(directive, _)| *directive == idx).map_or(default, |(_, level)| *level);
+            let filter: LevelFilter = LEVELS[level].parse().unwrap();
+            for target in module_paths(component) {
+                for event_level in [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE] {
+                    prop_assert_eq!(targets.would_enable(&target, &event_level), filter >= event_level);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn bad_directives_are_turned_away() {
+    let mut config = LogConfig::new(LevelFilter::WARN);
+    assert!(config.parse_levels("loud").is_err());
+    assert!(config.parse_levels("engine=debug").is_err());
+    assert!(config.parse_levels("match_maker=loud").is_err());
+    assert_eq!(config.level, LevelFilter::WARN);
+    assert!(config.components.is_empty());
+
+    // outside the crate only the default applies
+    assert!(config.parse_levels(" match_maker = debug ,").is_ok());
+    assert!(config.targets().would_enable("figgie_auto::match_maker", &Level::DEBUG));
+    assert!(!config.targets().would_enable("figgie_auto::player::generic", &Level::DEBUG));
+    assert!(!config.targets().would_enable("tokio::runtime", &Level::INFO));
+
+    assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+    assert_eq!("text".parse::<LogFormat>(), Ok(LogFormat::Text));
+    assert!("yaml".parse::<LogFormat>().is_err());
+}
+
+struct Recorder(Arc<Mutex<Vec<(String, Level, bool)>>>);
+
+impl<S: Subscriber> Layer<S> for Recorder {
+    fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+        let metadata = event.metadata();
+        self.0.lock().unwrap().push((metadata.target().to_string(), *metadata.level(), is_narrative(metadata)));
+    }
+}
+
+#[test]
+fn commentary_is_logged_from_its_module() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(Recorder(events.clone())));
+
+    let mut test_engine = dealt_engine(3, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    let seller = engine.player_names[0].clone();
+    let card = SUITS.iter().position(|card| engine.player_inventories[&seller].get(card) > 0).unwrap();
+    step(engine, &Action { player: 0, card, buy: false, price: 5, cancel: false });
+    step(engine, &Action { player: 1, card, buy: true, price: 5, cancel: false });
+    engine.settle_round();
+    let report = engine.round_report();
+    engine.analytics.record(report);
+
+    let events = events.lock().unwrap();
+    let narrative: Vec<&(String, Level, bool)> = events.iter().filter(|(_, _, narrative)| *narrative).collect();
+    for target in ["figgie_auto::match_maker", "figgie_auto::analytics", "figgie_auto::analytics::markout"] {
+        assert!(narrative.iter().any(|(event_target, _, _)| event_target == target), "nothing logged from {}", target);
+    }
+
+    // every event belongs to a component, so `--log` reaches all of them
+    let mut config = LogConfig::new(LevelFilter::OFF);
+    config.parse_levels(&COMPONENTS.map(|component| format!("{}=trace", component)).join(",")).unwrap();
+    for (target, level, _) in events.iter() {
+        assert!(config.targets().would_enable(target, level), "{} isn't under a component", target);
+    }
+    config.parse_levels("match_maker=off").unwrap();
+    assert!(narrative.iter().filter(|(target, _, _)| target.starts_with("figgie_auto::match_maker")).all(|(target, level, _)| !config.targets().would_enable(target, level)));
+}
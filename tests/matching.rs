@@ -0,0 +1,192 @@
+mod common;
+
+use common::*;
+use figgie_auto::{Checkpoint, Direction, Request, PlayerName, SelfTradePrevention};
+use proptest::prelude::*;
+
+
+// =-= Matching =-= //
+// - random order streams against a freshly dealt, seeded table
+// - every property is checked after every single request, not just at the end
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    // no card or point is ever created or destroyed by trading, and every resting quote stays backed
+    #[test]
+    fn trading_conserves_cards_and_points(
+        seed in any::<u64>(),
+        players in 3..=6usize,
+        stp in self_trade_prevention(),
+        actions in prop::collection::vec(action(), 0..300),
+    ) {
+        let mut test_engine = dealt_engine(seed, players, stp);
+        let engine = &mut test_engine.engine;
+        let bank = engine.bank;
+        let dealt = engine.held_cards();
+
+        for action in actions.iter() {
+            step(engine, action);
+
+            let held = engine.held_cards();
+            for card in SUITS.iter() {
+                prop_assert_eq!(held.get(card), dealt.get(card), "{} changed hands without being conserved", card.plural());
+            }
+            prop_assert_eq!(engine.held_points() + engine.pot, bank);
+            let violations = engine.audit(Checkpoint::Trade);
+            prop_assert!(violations.is_empty(), "{:?}", violations);
+        }
+    }
+
+    // a trade only happens at the resting price, the aggressor never pays more (or sells for less) than their limit,
+    // and an order that crosses someone else's quote always trades with it
+    #[test]
+    fn orders_never_trade_through(
+        seed in any::<u64>(),
+        players in 3..=6usize,
+        stp in self_trade_prevention(),
+        actions in prop::collection::vec(action(), 0..300),
+    ) {
+        let mut test_engine = dealt_engine(seed, players, stp);
+        let engine = &mut test_engine.engine;
+
+        for action in actions.iter() {
+            let step = step(engine, action);
+            let Request::Order(order) = &step.request else {
+                prop_assert!(step.trade.is_none());
+                continue;
+            };
+            let before = &step.before[&order.card];
+            let resting = match order.direction {
+                Direction::Buy => &before.ask,
+                Direction::Sell => &before.bid,
+            };
+            let crosses = match order.direction {
+                Direction::Buy => order.price >= resting.price,
+                Direction::Sell => order.price <= resting.price,
+            };
+
+            match &step.trade {
+                Some(trade) => {
+                    prop_assert!(step.valid);
+                    prop_assert!(crosses);
+                    prop_assert_eq!(trade.price, resting.price);
+                    prop_assert_eq!(trade.resting_seq, resting.seq);
+                    prop_assert!(resting.player_name != PlayerName::None);
+                    let (aggressor, counterparty) = match order.direction {
+                        Direction::Buy => (&trade.buyer, &trade.seller),
+                        Direction::Sell => (&trade.seller, &trade.buyer),
+                    };
+                    prop_assert_eq!(aggressor, &order.player_name);
+                    prop_assert_eq!(counterparty, &resting.player_name);
+                    if stp != SelfTradePrevention::Allow {
+                        prop_assert!(trade.buyer != trade.seller);
+                    }
+
+                    // everything quoted was priced off the old books
+                    for book in engine.books.values() {
+                        prop_assert_eq!(quoted(book), (false, false));
+                    }
+                },
+                None => {
+                    let someone_else = resting.player_name != PlayerName::None && resting.player_name != order.player_name;
+                    prop_assert!(!(step.valid && crosses && someone_else), "{:?} crossed {:?} without trading", order, resting);
+                },
+            }
+        }
+    }
+
+    // a quote only ever moves to a strictly better price, and only the player who owns a quote can pull it
+    #[test]
+    fn quotes_only_improve(
+        seed in any::<u64>(),
+        players in 3..=6usize,
+        stp in self_trade_prevention(),
+        actions in prop::collection::vec(action(), 0..300),
+    ) {
+        let mut test_engine = dealt_engine(seed, players, stp);
+        let engine = &mut test_engine.engine;
+
+        for action in actions.iter() {
+            let step = step(engine, action);
+            if step.trade.is_some() {
+                continue;
+            }
+
+            match &step.request {
+                Request::Order(order) => {
+                    let before = &step.before[&order.card];
+                    let after = &engine.books[&order.card];
+                    let (was, now) = match order.direction {
+                        Direction::Buy => (&before.bid, &after.bid),
+                        Direction::Sell => (&before.ask, &after.ask),
+                    };
+                    let unchanged = now.price == was.price && now.player_name == was.player_name;
+                    if !unchanged {
+                        prop_assert!(step.valid);
+                        prop_assert_eq!(now.price, order.price);
+                        prop_assert_eq!(&now.player_name, &order.player_name);
+                        match order.direction {
+                            Direction::Buy => prop_assert!(now.price > was.price),
+                            Direction::Sell => prop_assert!(now.price < was.price),
+                        }
+                    }
+
+                    // nothing else moves, bar the player's own quote pulled by `cancel-resting`
+                    for card in SUITS.iter() {
+                        let (before, after) = (&step.before[card], &engine.books[card]);
+                        let (other_was, other_now) = match order.direction {
+                            Direction::Buy => (&before.ask, &after.ask),
+                            Direction::Sell => (&before.bid, &after.bid),
+                        };
+                        let pulled = stp == SelfTradePrevention::CancelResting
+                            && *card == order.card
+                            && other_was.player_name == order.player_name
+                            && other_now.player_name == PlayerName::None;
+                        prop_assert!(pulled || other_now.price == other_was.price && other_now.player_name == other_was.player_name);
+                        if *card != order.card {
+                            prop_assert_eq!(after.bid.price, before.bid.price);
+                            prop_assert_eq!(after.ask.price, before.ask.price);
+                        }
+                    }
+                },
+                Request::Cancel(cancel) => {
+                    let before = &step.before[&cancel.card];
+                    let after = &engine.books[&cancel.card];
+                    let (was, now) = match cancel.direction {
+                        Direction::Buy => (&before.bid, &after.bid),
+                        Direction::Sell => (&before.ask, &after.ask),
+                    };
+                    if was.player_name == cancel.player_name {
+                        prop_assert_eq!(&now.player_name, &PlayerName::None);
+                    } else {
+                        prop_assert_eq!(now.price, was.price);
+                        prop_assert_eq!(&now.player_name, &was.player_name);
+                    }
+                },
+                Request::Disconnect(_) => {}, // never generated
+            }
+        }
+    }
+
+    // with both sides quoted, the bid is always below the ask
+    #[test]
+    fn books_never_cross(
+        seed in any::<u64>(),
+        players in 3..=6usize,
+        stp in self_trade_prevention(),
+        actions in prop::collection::vec(action(), 0..300),
+    ) {
+        let mut test_engine = dealt_engine(seed, players, stp);
+        let engine = &mut test_engine.engine;
+
+        for action in actions.iter() {
+            step(engine, action);
+            for (card, book) in engine.books.iter() {
+                if quoted(book) == (true, true) {
+                    prop_assert!(book.bid.price < book.ask.price, "{:?} is crossed: {:?}", card, book);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use figgie_auto::match_maker::Histogram;
+use figgie_auto::{Metrics, SelfTradePrevention};
+use proptest::prelude::*;
+
+
+// =-= Metrics =-= //
+// - a histogram's quantile is never below the real one, and what's since a copy of itself is nothing
+// - the counters add up to what the engine did, and the exposition is well formed
+
+const BOUNDS: [f64; 5] = [1.0, 2.0, 5.0, 10.0, 50.0];
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    fn quantiles_are_upper_bounds(values in prop::collection::vec(0.0..100.0f64, 1..200), quantile in 0.01..1.0f64) {
+        let mut histogram = Histogram::new(&BOUNDS);
+        for value in values.iter() {
+            histogram.observe(*value);
+        }
+        let mut sorted = values.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let rank = ((quantile * values.len() as f64).ceil() as usize).max(1);
+        prop_assert!(histogram.quantile(quantile).unwrap() >= sorted[rank - 1]);
+        prop_assert_eq!(histogram.count, values.len() as u64);
+        prop_assert_eq!(histogram.since(&histogram).count, 0);
+    }
+
+    #[test]
+    fn counters_follow_the_engine(seed in any::<u64>(), players in 3..=6usize, actions in prop::collection::vec(action(), 1..150)) {
+        let mut test_engine = dealt_engine(seed, players, SelfTradePrevention::Reject);
+        let engine = &mut test_engine.engine;
+        let before = engine.metrics.clone();
+        for action in actions.iter() {
+            step(engine, action);
+        }
+        let metrics = engine.metrics.since(&before);
+
+        // every request is answered at least once, and every rejection has its reason
+        prop_assert!(metrics.acks.values().sum::<u64>() >= actions.len() as u64);
+        prop_assert_eq!(metrics.rejected.values().sum::<u64>(), metrics.acks.get("rejected").copied().unwrap_or(0));
+        prop_assert_eq!(metrics.trades.values().sum::<u64>(), engine.round_trades.len() as u64);
+        prop_assert_eq!(metrics.acks.get("filled").copied().unwrap_or(0), engine.round_trades.len() as u64);
+        prop_assert_eq!(metrics.drained.count, engine.round_trades.len() as u64);
+        prop_assert_eq!(metrics.dropped, metrics.acks.get("dropped").copied().unwrap_or(0));
+    }
+}
+
+#[test]
+fn exposition_is_well_formed() {
+    let mut metrics = Metrics::new();
+    metrics.record_broadcast(3, 2);
+    metrics.record_queue(4, 1);
+    metrics.record_processing(std::time::Duration::from_micros(30), std::time::Duration::from_millis(2));
+
+    let text = metrics.render();
+    let mut typed = Vec::new();
+    for line in text.lines() {
+        if let Some(kind) = line.strip_prefix("# TYPE ") {
+            typed.push(kind.split_whitespace().next().unwrap().to_string());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        // every sample belongs to a declared metric and has a number
+        let (name, value) = line.rsplit_once(' ').unwrap();
+        let name = name.split('{').next().unwrap();
+        assert!(typed.iter().any(|metric| name.starts_with(metric.as_str())), "{}", line);
+        assert!(value.parse::<f64>().is_ok(), "{}", line);
+    }
+
+    assert!(text.contains("figgie_event_backlog 3\n"));
+    assert!(text.contains("figgie_queue_depth_bucket{le=\"5\"} 1\n"));
+    assert!(text.contains("figgie_processing_seconds_bucket{le=\"+Inf\"} 1\n"));
+    assert!(text.contains("figgie_processing_seconds_bucket{le=\"0.000025\"} 0\n"));
+    assert!(text.contains("figgie_processing_seconds_bucket{le=\"0.00005\"} 1\n"));
+
+    let summary = metrics.summary();
+    assert!(summary.contains(&String::from(" - Queue depth: p50 <= 5 | p99 <= 5")), "{:?}", summary);
+    assert!(summary.contains(&String::from(" - Processing: p50 <= 50µs | p99 <= 50µs")), "{:?}", summary);
+}
@@ -0,0 +1,122 @@
+use figgie_auto::player::{StrategyParams, PickOffParams, SellerParams};
+use figgie_auto::tuning::{grid, grid_size};
+use figgie_auto::PlayerName;
+use proptest::prelude::*;
+
+
+// =-= Strategy Parameters =-= //
+// - the defaults play exactly like the numbers the strategies were written with
+// - whatever the tuner sets, the parameters stay in range and in a shape the strategies can play
+
+fn strategy() -> impl Strategy<Value = StrategyParams> {
+    prop_oneof![Just(PlayerName::Seller), Just(PlayerName::PickOff), Just(PlayerName::TiltInventory)]
+        .prop_map(|player_name| StrategyParams::for_player(&player_name).unwrap())
+}
+
+#[test]
+fn defaults_match_the_original_thresholds() {
+    let pick_off = PickOffParams::default();
+    for seconds_left in 0..=240u64 {
+        let expected = if seconds_left < 20 {
+            (0, 0)
+        } else if seconds_left < 40 {
+            (2, 3)
+        } else if seconds_left < 60 {
+            (3, 4)
+        } else if seconds_left < 120 {
+            (4, 6)
+        } else {
+            (5, 8)
+        };
+        let bucket = pick_off.bucket(seconds_left);
+        assert_eq!((bucket.open, bucket.close), expected, "{}s left", seconds_left);
+    }
+
+    let seller = SellerParams::default();
+    for seconds_left in 0..=240u64 {
+        let expected = if seconds_left >= 180 {
+            (6, 8)
+        } else if seconds_left > 120 && seconds_left < 180 {
+            (5, 6)
+        } else if seconds_left > 60 && seconds_left < 120 {
+            (4, 6)
+        } else {
+            (3, 4)
+        };
+        let phase = seller.phase(seconds_left);
+        assert_eq!((phase.market, phase.limit), expected, "{}s left", seconds_left);
+    }
+}
+
+#[test]
+fn grids_start_from_the_defaults() {
+    // a search over any one parameter can always come back with the number it started from
+    for player_name in [PlayerName::Seller, PlayerName::PickOff, PlayerName::TiltInventory] {
+        for param in StrategyParams::for_player(&player_name).unwrap().params() {
+            assert!(param.values().contains(&param.value), "{:?} {:?}", player_name, param);
+        }
+    }
+}
+
+#[test]
+fn tuned_sets_are_labelled_apart_from_the_defaults() {
+    let mut params = StrategyParams::for_player(&PlayerName::Seller).unwrap();
+    assert_eq!(params.label(), "Seller");
+
+    params.set("phases[3].limit", 2).unwrap();
+    params.set("lower_frequency", 500).unwrap();
+    assert_eq!(params.label(), "Seller[lower_frequency=500 phases[3].limit=2]");
+    assert_eq!(params.player_name(), PlayerName::Seller);
+}
+
+proptest! {
+    #[test]
+    fn set_params_stay_playable(base in strategy(), changes in prop::collection::vec((any::<prop::sample::Index>(), -50..300i64), 1..30)) {
+        let mut params = base.clone();
+        for (idx, value) in changes.iter() {
+            let name = idx.get(&base.params()).name.clone();
+            prop_assert!(params.set(&name, *value).is_ok());
+        }
+
+        for param in params.params() {
+            prop_assert!(param.value >= param.min && param.value <= param.max, "{:?}", param);
+        }
+        match &params {
+            StrategyParams::Seller(seller) => {
+                prop_assert!(seller.lower_frequency < seller.higher_frequency);
+                prop_assert_eq!(seller.phases[3].from, 0);
+                for window in seller.phases.windows(2) {
+                    prop_assert!(window[0].from > window[1].from);
+                }
+            },
+            StrategyParams::PickOff(pick_off) => {
+                for window in pick_off.buckets.windows(2) {
+                    prop_assert!(window[0].below < window[1].below);
+                }
+            },
+            StrategyParams::TiltInventory(tilt) => prop_assert!(tilt.lower_frequency < tilt.higher_frequency),
+        }
+
+        // the tuner's output can be loaded back as is
+        let text = serde_json::to_string(&params).unwrap();
+        prop_assert_eq!(serde_json::from_str::<StrategyParams>(&text).unwrap(), params);
+    }
+
+    #[test]
+    fn grid_covers_every_combination(base in strategy(), picks in prop::collection::vec(any::<prop::sample::Index>(), 1..3)) {
+        // prices don't interact through `normalise`, so none of their combinations collapse into each other
+        let prices: Vec<String> = base.params().into_iter().filter(|param| param.max <= 15).map(|param| param.name).collect();
+        let mut names: Vec<String> = picks.iter().map(|idx| idx.get(&prices).clone()).collect();
+        names.dedup();
+        prop_assume!(names.len() == picks.len());
+
+        let grid = grid(&base, &names);
+        prop_assert_eq!(grid.len(), grid_size(&base, &names));
+        for params in grid.iter() {
+            for param in params.params().into_iter().filter(|param| !names.contains(&param.name)) {
+                let original = base.params().into_iter().find(|other| other.name == param.name).unwrap();
+                prop_assert_eq!(param.value, original.value);
+            }
+        }
+    }
+}
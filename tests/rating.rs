@@ -0,0 +1,52 @@
+use figgie_auto::rating::{Ratings, INITIAL_RATING};
+use proptest::prelude::*;
+
+
+// =-= Ratings =-= //
+// - Elo only ever moves rating points between the players at the table, never makes or loses them
+// - finishing ahead of an equal field is always worth something, finishing behind it always costs
+
+fn standings() -> impl Strategy<Value = Vec<(String, i64)>> {
+    prop::collection::vec(-200..=200i64, 2..=6)
+        .prop_map(|points| points.into_iter().enumerate().map(|(idx, points)| (format!("p{}", idx), points)).collect())
+}
+
+proptest! {
+    #[test]
+    fn ratings_are_zero_sum(results in prop::collection::vec(standings(), 1..20)) {
+        let mut ratings = Ratings::default();
+        for standings in results.iter() {
+            let before: f64 = standings.iter().map(|(label, _)| ratings.get(label).rating).sum();
+            ratings.update(standings);
+            let after: f64 = standings.iter().map(|(label, _)| ratings.get(label).rating).sum();
+            prop_assert!((before - after).abs() < 1e-6, "{} -> {}", before, after);
+        }
+    }
+
+    #[test]
+    fn placing_moves_an_equal_field(standings in standings()) {
+        let mut ratings = Ratings::default();
+        ratings.update(&standings);
+
+        let best = standings.iter().map(|(_, points)| *points).max().unwrap();
+        let worst = standings.iter().map(|(_, points)| *points).min().unwrap();
+        for (label, points) in standings.iter() {
+            let rating = ratings.get(label);
+            prop_assert_eq!(rating.results, 1);
+            prop_assert_eq!(rating.wins, usize::from(*points == best));
+            if best != worst && *points == best {
+                prop_assert!(rating.rating > INITIAL_RATING);
+            }
+            if best != worst && *points == worst {
+                prop_assert!(rating.rating < INITIAL_RATING);
+            }
+        }
+
+        // the order players are listed in doesn't matter
+        let mut reversed = Ratings::default();
+        reversed.update(&standings.iter().rev().cloned().collect::<Vec<_>>());
+        for (label, _) in standings.iter() {
+            prop_assert!((reversed.get(label).rating - ratings.get(label).rating).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+use figgie_auto::analytics::Tables;
+use figgie_auto::report::{Session, render};
+use figgie_auto::{PlayerName, SelfTradePrevention};
+use std::fs;
+
+
+// =-= Report =-= //
+// - a recorded session reads back in whole, and every settled round gets its section, charts and reveal
+
+#[test]
+fn recorded_sessions_read_back() {
+    let dir = std::env::temp_dir().join(format!("figgie-report-{}", std::process::id()));
+    let mut test_engine = engine(7, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    engine.player_names[0] = PlayerName::External(String::from("<b>bob</b>, jr"));
+    engine.player_points.insert(engine.player_names[0].clone(), STARTING_BALANCE);
+    engine.player_inventories.insert(engine.player_names[0].clone(), Default::default());
+    engine.tables = Tables::new(Some(dir.clone()));
+
+    let mut trades = 0;
+    for round in 0..2 {
+        engine.round = round;
+        engine.round_trades.clear();
+        engine.collect_ante(50);
+        engine.deal();
+        // everyone offers what they hold a bit cheaper each time, and the next seat takes it
+        for price in (1..12).rev() {
+            for (card, seat) in SUITS.iter().enumerate().flat_map(|(card, _)| (0..4).map(move |seat| (card, seat))) {
+                step(engine, &Action { player: seat, card, buy: false, price, cancel: false });
+                step(engine, &Action { player: seat + 1, card, buy: true, price, cancel: false });
+            }
+        }
+        engine.settle_round();
+        trades += engine.round_trades.len();
+        let report = engine.round_report();
+        engine.tables.record_round(&report, &engine.round_trades, &engine.player_points).unwrap();
+    }
+    assert!(trades > 0);
+
+    let session = Session::load(&dir).unwrap();
+    assert_eq!(session.rounds(), vec![0, 1]);
+    assert_eq!(session.trades.len(), trades);
+    assert_eq!(session.players.len(), 4);
+    assert_eq!(session.players[0], "<b>bob</b>, jr");
+    assert_eq!(session.results.iter().map(|result| result.pnl).sum::<i64>(), 0);
+
+    let html = render(&session, "test");
+    assert_eq!(html.matches("<section><h2>Round").count(), 2);
+    assert_eq!(html.matches("<details>").count(), 2);
+    assert_eq!(html.matches("<circle").count() - session.results.len() - 4, trades); // a dot per trade, plus the points curve's
+    assert!(!html.contains("<b>bob</b>"));
+    assert!(html.contains("&lt;b&gt;bob&lt;/b&gt;, jr"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use common::*;
+use figgie_auto::{Checkpoint, Card, Inventory, SelfTradePrevention};
+use proptest::prelude::*;
+
+
+// =-= Settlement =-= //
+// - deals are checked against the rules of the deck, and come out the same for the same seed
+// - settlement pays 10 a goal card and splits whatever's left of the pot evenly between everyone tied for the most
+// - an uneven split gives its odd points to the first tied seat, so the pot is empty after every round and no points leave the table
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn deals_follow_the_deck(seed in any::<u64>(), players in 3..=6usize) {
+        let mut test_engine = engine(seed, players, SelfTradePrevention::Reject);
+        let engine = &mut test_engine.engine;
+        let starting_inventory = engine.deal();
+
+        let mut counts: Vec<usize> = SUITS.iter().map(|card| starting_inventory[card]).collect();
+        counts.sort();
+        prop_assert_eq!(counts, vec![8, 10, 10, 12]);
+        prop_assert_eq!(starting_inventory[&engine.common_suit], 12);
+        prop_assert_eq!(engine.goal_suit.clone(), engine.common_suit.get_goal_suit());
+        prop_assert!(starting_inventory[&engine.goal_suit] < 12);
+
+        // every seat gets the same number of cards, any left over aren't dealt
+        let hand = 40 / players;
+        for player_name in engine.player_names.iter() {
+            let inventory = engine.player_inventories[player_name];
+            let held: usize = SUITS.iter().map(|card| inventory.get(card)).sum();
+            prop_assert_eq!(held, hand);
+        }
+        for card in SUITS.iter() {
+            prop_assert!(engine.dealt_cards.get(card) <= starting_inventory[card]);
+        }
+    }
+
+    #[test]
+    fn deals_are_seeded(seed in any::<u64>(), players in 3..=6usize) {
+        let mut first = engine(seed, players, SelfTradePrevention::Reject);
+        let mut second = engine(seed, players, SelfTradePrevention::Reject);
+        for _ in 0..3 {
+            first.engine.deal();
+            second.engine.deal();
+            prop_assert_eq!(&first.engine.common_suit, &second.engine.common_suit);
+            for player_name in first.engine.player_names.iter() {
+                let (a, b) = (first.engine.player_inventories[player_name], second.engine.player_inventories[player_name]);
+                for card in SUITS.iter() {
+                    prop_assert_eq!(a.get(card), b.get(card));
+                }
+            }
+        }
+    }
+
+    // hands are made up directly so ties come up far more often than they would from a real deal
+    #[test]
+    fn majority_bonus_is_split_between_the_tied(
+        goal_cards in prop::collection::vec(0..=5usize, 3..=6),
+        bonus in 0..=200usize,
+    ) {
+        let mut test_engine = engine(0, goal_cards.len(), SelfTradePrevention::Reject);
+        let engine = &mut test_engine.engine;
+        engine.goal_suit = Card::Heart;
+        for (player_name, hearts) in engine.player_names.clone().iter().zip(goal_cards.iter()) {
+            let mut inventory = Inventory::new();
+            inventory.hearts = *hearts;
+            inventory.spades = 2; // not the goal suit, so worth nothing
+            engine.player_inventories.insert(player_name.clone(), inventory);
+        }
+        let pot = goal_cards.iter().sum::<usize>() * 10 + bonus;
+        engine.pot = pot;
+        let before = engine.player_points.clone();
+
+        engine.settle_round();
+
+        let most = *goal_cards.iter().max().unwrap();
+        let tied = goal_cards.iter().filter(|hearts| **hearts == most).count();
+        let first = goal_cards.iter().position(|hearts| *hearts == most).unwrap();
+        for (idx, (player_name, hearts)) in engine.player_names.iter().zip(goal_cards.iter()).enumerate() {
+            let won = engine.player_points[player_name] - before[player_name];
+            let share = match *hearts == most {
+                true if idx == first => bonus / tied + bonus % tied,
+                true => bonus / tied,
+                false => 0,
+            };
+            prop_assert_eq!(won, hearts * 10 + share);
+        }
+        prop_assert_eq!(engine.pot, 0);
+    }
+
+    // round after round, ante to payout, every point stays on the table and the pot is emptied
+    #[test]
+    fn rounds_settle_the_whole_pot(
+        seed in any::<u64>(),
+        players in 3..=6usize,
+        stp in self_trade_prevention(),
+        rounds in prop::collection::vec(prop::collection::vec(action(), 0..150), 1..4),
+    ) {
+        let mut test_engine = engine(seed, players, stp);
+        let engine = &mut test_engine.engine;
+        let bank = engine.held_points();
+        for actions in rounds.iter() {
+            engine.collect_ante(200 / players);
+            engine.deal();
+            engine.pull_uncovered_quotes();
+            for action in actions.iter() {
+                step(engine, action);
+            }
+            engine.settle_round();
+
+            prop_assert_eq!(engine.pot, 0);
+            prop_assert_eq!(engine.held_points(), bank);
+            let violations = engine.audit(Checkpoint::Settlement);
+            prop_assert!(violations.is_empty(), "{:?}", violations);
+        }
+    }
+}
+
+#[test]
+fn uneven_ties_keep_every_point() {
+    // 4 seats ante 50 each, 3 of them tie on a heart apiece and split the 170 left over, 56 each and 2 odd points
+    let mut test_engine = engine(9, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    let bank = engine.held_points();
+    engine.collect_ante(50);
+    engine.goal_suit = Card::Heart;
+    for (player_name, hearts) in engine.player_names.clone().iter().zip([1, 1, 1, 0]) {
+        let mut inventory = Inventory::new();
+        inventory.hearts = hearts;
+        inventory.spades = 9;
+        engine.player_inventories.insert(player_name.clone(), inventory);
+    }
+    engine.settle_round();
+    assert_eq!(engine.pot, 0);
+    assert_eq!(engine.held_points(), bank);
+    let points: Vec<usize> = engine.player_names.iter().map(|player_name| engine.player_points[player_name]).collect();
+    assert_eq!(points, vec![450 + 10 + 58, 450 + 10 + 56, 450 + 10 + 56, 450]);
+
+    // and the next round starts from the same total
+    engine.collect_ante(50);
+    engine.deal();
+    engine.settle_round();
+    assert_eq!(engine.pot, 0);
+    assert_eq!(engine.held_points(), bank);
+}
+
+#[test]
+fn audits_stay_clean_after_an_uneven_tie() {
+    let mut test_engine = engine(11, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+    engine.audit_mode = true;
+    engine.collect_ante(50);
+    engine.goal_suit = Card::Heart;
+    for (player_name, hearts) in engine.player_names.clone().iter().zip([1, 1, 1, 0]) {
+        let mut inventory = Inventory::new();
+        inventory.hearts = hearts;
+        inventory.spades = 9;
+        engine.player_inventories.insert(player_name.clone(), inventory);
+    }
+    engine.dealt_cards = engine.held_cards();
+    engine.settle_round();
+    engine.run_audit(Checkpoint::Settlement);
+    assert_eq!(engine.audit_violations, 0);
+
+    // the next round is checked against the same table, with nothing rebased in between
+    engine.collect_ante(50);
+    engine.deal();
+    engine.run_audit(Checkpoint::Trade);
+    for action in [Action { player: 0, card: 0, buy: false, price: 3, cancel: false }, Action { player: 1, card: 0, buy: true, price: 3, cancel: false }] {
+        step(engine, &action);
+        engine.run_audit(Checkpoint::Trade);
+    }
+    engine.settle_round();
+    engine.run_audit(Checkpoint::Settlement);
+    assert_eq!(engine.audit_violations, 0);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::*;
+use figgie_auto::spectator::{Spectator, TableView};
+use figgie_auto::{Event, SelfTradePrevention};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+
+// =-= Spectator =-= //
+// - nobody watching can see a hand or the suits before the round is settled, whether from the snapshot or the stream
+
+#[test]
+fn hands_stay_hidden_until_settled() {
+    let mut test_engine = dealt_engine(3, 5, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+
+    // dealt but not trading yet, then trading
+    engine.publish_snapshot();
+    let dealt = TableView::new(&engine.snapshot_sender.borrow());
+    engine.trading = true;
+    engine.round_start = Some(tokio::time::Instant::now());
+    engine.publish_snapshot();
+    let trading = TableView::new(&engine.snapshot_sender.borrow());
+    for view in [&dealt, &trading] {
+        assert!(!view.revealed);
+        assert!(view.goal_suit.is_none() && view.common_suit.is_none());
+        assert!(view.players.iter().all(|player| player.hand.is_none()));
+        assert_eq!(view.players.iter().map(|player| player.cards).sum::<usize>(), engine.dealt_cards.total());
+    }
+
+    engine.trading = false;
+    engine.settle_round();
+    engine.publish_snapshot();
+    let settled = TableView::new(&engine.snapshot_sender.borrow());
+    assert!(settled.revealed);
+    assert_eq!(settled.goal_suit, Some(engine.goal_suit.clone()));
+    for player in settled.players.iter() {
+        assert_eq!(player.hand, engine.player_inventories.get(&player.name).copied());
+    }
+}
+
+#[tokio::test]
+async fn events_stream_to_spectators() {
+    let mut test_engine = dealt_engine(5, 4, SelfTradePrevention::Reject);
+    let engine = &mut test_engine.engine;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let spectator = Spectator::new(address.to_string(), engine.event_sender.clone(), engine.snapshot_sender.subscribe());
+    tokio::task::spawn(async move {
+        spectator.serve(listener).await;
+    });
+
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream.write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    let mut lines = BufReader::new(stream).lines();
+    assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("HTTP/1.1 200 OK"));
+    while lines.next_line().await.unwrap().is_some_and(|line| !line.is_empty()) {}
+
+    // a whole round as spectators see it, the ack in the middle is only for its seat
+    engine.event_sender.send(Event::DealCards(engine.player_inventories.clone())).unwrap();
+    step(engine, &Action { player: 0, card: 0, buy: true, price: 0, cancel: false }); // turned away, so only an ack goes out
+    engine.round_start = Some(tokio::time::Instant::now());
+    engine.event_sender.send(Event::EndRound).unwrap();
+    engine.settle_round();
+    engine.publish_snapshot();
+    let settled = engine.round;
+    engine.round += 1;
+
+    let mut messages = Vec::new();
+    while messages.len() < 3 {
+        let line = lines.next_line().await.unwrap().unwrap();
+        let Some(data) = line.strip_prefix("data: ") else { continue };
+        messages.push(serde_json::from_str::<serde_json::Value>(data).unwrap());
+    }
+    let types: Vec<&str> = messages.iter().map(|message| message["type"].as_str().unwrap()).collect();
+    assert_eq!(types, vec!["deal", "end_round", "reveal"]);
+
+    // counts at the deal, hands at the reveal
+    let deal = messages[0]["cards"].as_object().unwrap();
+    assert_eq!(deal.values().map(|cards| cards.as_u64().unwrap() as usize).sum::<usize>(), engine.dealt_cards.total());
+    assert!(!messages[0].to_string().contains("spades"));
+    assert_eq!(messages[2]["revealed"], true);
+    assert_eq!(messages[2]["round"], settled);
+    assert!(messages[2]["players"].as_array().unwrap().iter().all(|player| player["hand"].is_object()));
+}
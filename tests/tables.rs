@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+use figgie_auto::analytics::{Tables, csv_field, parse_csv_line, TRADES_HEADER, QUOTES_HEADER, DEALS_HEADER, RESULTS_HEADER};
+use figgie_auto::SelfTradePrevention;
+use proptest::prelude::*;
+use std::fs;
+use std::path::Path;
+
+
+// =-= Tables =-= //
+// - the CSV tables say the same thing as the engine: every trade once, every hand as dealt, results that add back up
+// - a quote row is only written when that suit's top of book actually moved
+
+fn rows(dir: &Path, file: &str, header: &str) -> Vec<Vec<String>> {
+    let text = fs::read_to_string(dir.join(file)).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some(header));
+    let columns = header.split(',').count();
+    lines.map(|line| {
+        let row: Vec<String> = line.split(',').map(String::from).collect();
+        assert_eq!(row.len(), columns, "{}: {}", file, line);
+        row
+    }).collect()
+}
+
+#[test]
+fn fields_are_escaped() {
+    assert_eq!(csv_field("Spread"), "Spread");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(50))]
+
+    #[test]
+    fn fields_read_back(fields in prop::collection::vec("[a-z,\" ]{0,8}", 1..6)) {
+        let line = fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+        prop_assert_eq!(parse_csv_line(&line), fields);
+    }
+
+    #[test]
+    fn tables_match_the_engine(seed in any::<u64>(), players in 3..=6usize, actions in prop::collection::vec(action(), 0..150)) {
+        let dir = std::env::temp_dir().join(format!("figgie-tables-{}-{}", std::process::id(), seed));
+        let mut test_engine = engine(seed, players, SelfTradePrevention::Reject);
+        let engine = &mut test_engine.engine;
+        engine.tables = Tables::new(Some(dir.clone()));
+
+        engine.collect_ante(200 / players);
+        engine.deal();
+        for action in actions.iter() {
+            step(engine, action);
+        }
+        engine.settle_round();
+        let report = engine.round_report();
+        engine.tables.record_round(&report, &engine.round_trades, &engine.player_points).unwrap();
+
+        let trades = rows(&dir, "trades.csv", TRADES_HEADER);
+        prop_assert_eq!(trades.len(), engine.round_trades.len());
+        for (row, trade) in trades.iter().zip(engine.round_trades.iter()) {
+            prop_assert_eq!(&row[1], &trade.seq.to_string());
+            prop_assert_eq!(&row[4], &trade.price.to_string());
+        }
+
+        let deals = rows(&dir, "deals.csv", DEALS_HEADER);
+        prop_assert_eq!(deals.len(), players);
+        let dealt: usize = deals.iter().flat_map(|row| row[2..6].iter()).map(|count| count.parse::<usize>().unwrap()).sum();
+        prop_assert_eq!(dealt, engine.dealt_cards.total());
+
+        let results = rows(&dir, "results.csv", RESULTS_HEADER);
+        prop_assert_eq!(results.len(), players);
+        // PnL never leaves the table
+        prop_assert_eq!(results.iter().map(|row| row[6].parse::<i64>().unwrap()).sum::<i64>(), 0);
+        for (row, player_name) in results.iter().zip(engine.player_names.iter()) {
+            prop_assert_eq!(&row[8], &engine.player_points[player_name].to_string());
+        }
+
+        let quotes = rows(&dir, "quotes.csv", QUOTES_HEADER);
+        for suit in SUITS.iter() {
+            let suit = format!("{:?}", suit);
+            let moves: Vec<&[String]> = quotes.iter().filter(|row| row[2] == suit).map(|row| &row[3..]).collect();
+            for window in moves.windows(2) {
+                prop_assert_ne!(window[0], window[1]);
+            }
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
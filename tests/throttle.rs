@@ -0,0 +1,54 @@
+use figgie_auto::{Card, Cancel, Direction, Order, PlayerName, Request, Sequenced, Stamp, ThrottleAction, ThrottleConfig};
+use figgie_auto::match_maker::{Throttle, TokenBucket};
+use tokio::time::{Duration, Instant};
+
+
+// =-= Throttle =-= //
+// - with nothing asked for, every request goes straight through however fast it comes
+// - a bucket that never refills never has a token ready, so the match maker has nothing to wait on
+
+fn sequenced(seq: u64, request: Request) -> Sequenced {
+    Sequenced { stamp: Stamp { seq, timestamp: 0 }, request }
+}
+
+fn order(player_name: &PlayerName) -> Request {
+    Request::Order(Order { player_name: player_name.clone(), price: 5, direction: Direction::Buy, card: Card::Spade })
+}
+
+#[test]
+fn off_by_default() {
+    let player_name = PlayerName::Spread;
+    let mut throttle = Throttle::new(ThrottleConfig::default());
+    let now = Instant::now();
+    for seq in 0..1000 {
+        assert!(throttle.enqueue(sequenced(seq, order(&player_name))).is_ok());
+    }
+    for _ in 0..1000 {
+        assert!(matches!(throttle.next(now), Some(Ok(_))));
+    }
+    assert!(throttle.throttled.is_empty());
+}
+
+#[test]
+fn an_empty_bucket_without_a_rate_never_wakes() {
+    let config = ThrottleConfig { orders_per_second: 0.0, burst: 1.0, max_outstanding: 10, action: ThrottleAction::Delay };
+    let mut bucket = TokenBucket::new(0.0, Instant::now());
+    bucket.refill(&config, Instant::now() + Duration::from_secs(60));
+    assert_eq!(bucket.wait(&config), None);
+
+    let config = ThrottleConfig { orders_per_second: 4.0, ..config };
+    assert_eq!(bucket.wait(&config), Some(Duration::from_millis(250)));
+
+    // a delayed order with no token coming holds nothing up
+    let player_name = PlayerName::Seller;
+    let mut throttle = Throttle::new(ThrottleConfig { orders_per_second: 0.0, ..config });
+    throttle.buckets.insert(player_name.clone(), TokenBucket::new(0.0, Instant::now()));
+    throttle.enqueue(sequenced(0, order(&player_name))).unwrap();
+    assert!(throttle.next(Instant::now()).is_none());
+    assert_eq!(throttle.next_ready(), None);
+
+    // cancels still get through
+    let cancel = Cancel { player_name: player_name.clone(), direction: Direction::Buy, card: Card::Spade };
+    throttle.enqueue(sequenced(1, Request::Cancel(cancel))).unwrap();
+    assert!(matches!(throttle.next(Instant::now()), Some(Ok(_))));
+}
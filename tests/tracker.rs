@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+use figgie_auto::{CardTracker, Inventory, PlayerName, Trade, Direction, Card, SelfTradePrevention};
+use proptest::prelude::*;
+
+
+// =-= Card Tracker =-= //
+// - whatever one seat works out from its own deal and the tape has to hold for the real hands
+// - a sale the tape can't account for is a constraint on the seller's deal, a buy is a card they're known to hold
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    fn inferences_hold_for_the_real_hands(seed in any::<u64>(), players in 3..=6usize, seat in 0..6usize, actions in prop::collection::vec(action(), 0..150)) {
+        let mut test_engine = dealt_engine(seed, players, SelfTradePrevention::Reject);
+        let engine = &mut test_engine.engine;
+        let dealt = engine.player_inventories.clone();
+        let name = engine.player_names[seat % players].clone();
+        let mut tracker = CardTracker::new(name.clone());
+        tracker.deal(dealt[&name], engine.player_names.clone());
+
+        for action in actions.iter() {
+            step(engine, action);
+        }
+        for trade in engine.round_trades.iter() {
+            tracker.record_trade(trade);
+        }
+
+        for player_name in engine.player_names.iter() {
+            let held = engine.player_inventories[player_name];
+            prop_assert_eq!(tracker.min_holding(player_name, &Card::Spade) + tracker.min_holding(player_name, &Card::Club)
+                + tracker.min_holding(player_name, &Card::Diamond) + tracker.min_holding(player_name, &Card::Heart)
+                + tracker.unknown_cards(player_name), held.total());
+            for card in SUITS.iter() {
+                prop_assert!(tracker.min_holding(player_name, card) <= held.get(card));
+                prop_assert!(tracker.max_holding(player_name, card) >= held.get(card));
+            }
+        }
+        // our own hand is never a guess
+        for card in SUITS.iter() {
+            prop_assert_eq!(tracker.min_holding(&name, card), engine.player_inventories[&name].get(card));
+        }
+        for constraint in tracker.constraints() {
+            prop_assert!(constraint.player_name != name);
+            prop_assert!(constraint.at_least <= dealt[&constraint.player_name].get(&constraint.card), "{}", constraint);
+        }
+        for card in SUITS.iter() {
+            if let Some((leader, most)) = tracker.majority_leader(card) {
+                prop_assert!(engine.player_inventories[&leader].get(card) >= most);
+            }
+        }
+    }
+}
+
+fn trade(card: Card, seller: &PlayerName, buyer: &PlayerName) -> Trade {
+    Trade { card, price: 5, buyer: buyer.clone(), seller: seller.clone(), seq: 0, resting_seq: 0, aggressor: Direction::Buy, timestamp: 0 }
+}
+
+#[test]
+fn the_tape_reveals_hands() {
+    let (us, alice, bob) = (PlayerName::External(String::from("us")), PlayerName::External(String::from("alice")), PlayerName::External(String::from("bob")));
+    let mut hand = Inventory::new();
+    hand.spades = 4;
+    hand.hearts = 6;
+    let mut tracker = CardTracker::new(us.clone());
+    tracker.deal(hand, vec![us.clone(), alice.clone(), bob.clone()]);
+    assert_eq!(tracker.unknown_cards(&alice), 10);
+    assert!(tracker.constraints().is_empty());
+
+    // alice sells 2 hearts she was dealt, bob buys them and sells one back on
+    tracker.record_trade(&trade(Card::Heart, &alice, &bob));
+    tracker.record_trade(&trade(Card::Heart, &alice, &bob));
+    tracker.record_trade(&trade(Card::Heart, &bob, &us));
+    assert_eq!(tracker.min_holding(&bob, &Card::Heart), 1);
+    assert_eq!(tracker.min_holding(&alice, &Card::Heart), 0);
+    assert_eq!(tracker.min_holding(&us, &Card::Heart), 7);
+    assert_eq!(tracker.unknown_cards(&alice), 8);
+    assert_eq!(tracker.unknown_cards(&bob), 10);
+
+    // bob's sale came out of what he bought, so only alice's deal is pinned down
+    let constraints = tracker.constraints();
+    assert_eq!(constraints.len(), 1);
+    assert_eq!(constraints[0].to_string(), "External(\"alice\") held at least 2 Hearts");
+
+    assert_eq!(tracker.majority_leader(&Card::Heart), Some((us.clone(), 7)));
+    assert!(tracker.rival_could_exceed(&us, &Card::Heart, 7)); // alice and bob still have unseen cards
+    assert!(!tracker.rival_could_exceed(&us, &Card::Heart, 11));
+}